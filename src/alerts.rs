@@ -0,0 +1,482 @@
+//! User-defined threshold alerts on [`StreamData`] fields.
+//!
+//! The rules are saved to `alerts.json` in the working directory, or the file named by
+//! `ALERTS_FILE`, whenever they change, and loaded again at startup. Alerts themselves are not
+//! saved, as they are raised again by the first snapshots of the next run.
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use iced::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::sparkline::Field;
+use crate::store::Instrument;
+use crate::stream_data::StreamData;
+
+const DEFAULT_PATH: &str = "alerts.json";
+
+pub type RuleId = u64;
+
+/// Which side of the threshold raises an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    #[default]
+    Above,
+    Below,
+}
+
+impl Comparison {
+    pub const ALL: [Comparison; 2] = [Comparison::Above, Comparison::Below];
+
+    fn raises(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::Below => value < threshold,
+        }
+    }
+
+    fn clears(self, value: f64, threshold: f64, hysteresis: f64) -> bool {
+        match self {
+            Comparison::Above => value <= threshold - hysteresis,
+            Comparison::Below => value >= threshold + hysteresis,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Above => "above",
+            Comparison::Below => "below",
+        })
+    }
+}
+
+/// Raises an [`Alert`] when a [`Field`] crosses a threshold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: RuleId,
+    pub field: Field,
+    /// The instrument the rule watches. Every instrument if `None`.
+    pub instrument: Option<Instrument>,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// How far the value has to come back from the threshold before the alert clears, so a
+    /// value hovering around the threshold does not raise it over and over.
+    pub hysteresis: f64,
+}
+
+impl Rule {
+    fn watches(&self, instrument: &Instrument) -> bool {
+        self.instrument.as_ref().is_none_or(|watched| watched == instrument)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.instrument {
+            Some(instrument) => write!(f, "{} of {instrument}", self.field)?,
+            None => write!(f, "{} of any instrument", self.field)?,
+        }
+        write!(f, " {} {}", self.comparison, self.threshold)?;
+        if self.hysteresis > 0.0 {
+            write!(f, " (hysteresis {})", self.hysteresis)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`Rule`] that was raised for an instrument.
+///
+/// An alert stays listed until it is both cleared and acknowledged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub rule: RuleId,
+    pub field: Field,
+    pub instrument: Instrument,
+    /// The latest value of the field.
+    pub value: f64,
+    pub raised: Instant,
+    /// Whether the value has not come back past the threshold and hysteresis yet.
+    pub active: bool,
+    pub acknowledged: bool,
+}
+
+/// The alert [`Rule`]s and the [`Alert`]s they raised.
+#[derive(Debug, Clone, Default)]
+pub struct Alerts {
+    rules: Vec<Rule>,
+    alerts: BTreeMap<(RuleId, Instrument), Alert>,
+}
+
+impl Alerts {
+    /// Reads the saved rules, if any.
+    pub fn load() -> Result<Self, Error> {
+        let path = path();
+        let rules = match std::fs::read(&path) {
+            Ok(contents) => {
+                serde_json::from_slice(&contents).map_err(|error| Error::Parse(path, error))?
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(Error::Io(path, error)),
+        };
+
+        Ok(Self {
+            rules,
+            alerts: BTreeMap::new(),
+        })
+    }
+
+    /// Writes the rules, so they are loaded again on the next run.
+    pub fn save(&self) -> Result<(), Error> {
+        let path = path();
+        let contents = serde_json::to_vec_pretty(&self.rules)
+            .map_err(|error| Error::Parse(path.clone(), error))?;
+        std::fs::write(&path, contents).map_err(|error| Error::Io(path, error))
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.iter()
+    }
+
+    pub fn rule(&self, id: RuleId) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.id == id)
+    }
+
+    /// Adds a rule, ignoring its `id` in favor of a new one, and returns the new id.
+    pub fn add_rule(&mut self, rule: Rule) -> RuleId {
+        let id = self.rules.iter().map(|rule| rule.id + 1).max().unwrap_or(0);
+        self.rules.push(Rule { id, ..rule });
+        id
+    }
+
+    /// Removes a rule and every alert it raised.
+    pub fn remove_rule(&mut self, id: RuleId) {
+        self.rules.retain(|rule| rule.id != id);
+        self.alerts.retain(|(rule, _), _| *rule != id);
+    }
+
+    /// Checks a new snapshot against every rule watching its instrument and returns how many
+    /// alerts it raised.
+    pub fn evaluate(&mut self, data: &StreamData, time: Instant) -> usize {
+        let instrument = Instrument::of(data);
+        let mut raised = 0;
+
+        for rule in self.rules.iter().filter(|rule| rule.watches(&instrument)) {
+            let value = rule.field.value(data);
+            let key = (rule.id, instrument.clone());
+
+            match self.alerts.get_mut(&key) {
+                Some(alert) => {
+                    alert.value = value;
+
+                    if alert.active {
+                        alert.active =
+                            !rule.comparison.clears(value, rule.threshold, rule.hysteresis);
+                    } else if rule.comparison.raises(value, rule.threshold) {
+                        alert.active = true;
+                        alert.acknowledged = false;
+                        alert.raised = time;
+                        raised += 1;
+                    }
+
+                    if !alert.active && alert.acknowledged {
+                        let _ = self.alerts.remove(&key);
+                    }
+                }
+                None if rule.comparison.raises(value, rule.threshold) => {
+                    let _ = self.alerts.insert(
+                        key,
+                        Alert {
+                            rule: rule.id,
+                            field: rule.field,
+                            instrument: instrument.clone(),
+                            value,
+                            raised: time,
+                            active: true,
+                            acknowledged: false,
+                        },
+                    );
+                    raised += 1;
+                }
+                None => {}
+            }
+        }
+
+        raised
+    }
+
+    /// Acknowledges an alert, dropping it if it has already cleared.
+    pub fn acknowledge(&mut self, rule: RuleId, instrument: &Instrument) {
+        let key = (rule, instrument.clone());
+
+        if let Some(alert) = self.alerts.get_mut(&key) {
+            alert.acknowledged = true;
+
+            if !alert.active {
+                let _ = self.alerts.remove(&key);
+            }
+        }
+    }
+
+    pub fn acknowledge_all(&mut self) {
+        self.alerts.retain(|_, alert| alert.active);
+        self.alerts.values_mut().for_each(|alert| alert.acknowledged = true);
+    }
+
+    /// Returns the alerts, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Alert> {
+        let mut alerts: Vec<&Alert> = self.alerts.values().collect();
+        alerts.sort_by(|a, b| b.raised.cmp(&a.raised));
+        alerts.into_iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.alerts.len()
+    }
+
+    pub fn unacknowledged(&self) -> usize {
+        self.alerts.values().filter(|alert| !alert.acknowledged).count()
+    }
+
+    /// Whether any of the fields has an unacknowledged alert.
+    pub fn is_raised(&self, fields: &[Field]) -> bool {
+        self.alerts
+            .values()
+            .any(|alert| !alert.acknowledged && fields.contains(&alert.field))
+    }
+}
+
+fn path() -> PathBuf {
+    env::var_os("ALERTS_FILE").map_or_else(|| PathBuf::from(DEFAULT_PATH), PathBuf::from)
+}
+
+/// An error loading or saving the [`Rule`]s.
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, error) => write!(f, "cannot access {}: {error}", path.display()),
+            Self::Parse(path, error) => write!(f, "invalid {}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The instruments a [`Rule`] can watch, as listed when editing it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scope(pub Option<Instrument>);
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(instrument) => write!(f, "{instrument}"),
+            None => f.write_str("Any instrument"),
+        }
+    }
+}
+
+/// A [`Rule`] being edited, with its numbers as typed.
+#[derive(Debug, Clone, Default)]
+pub struct Draft {
+    pub field: Field,
+    pub scope: Scope,
+    pub comparison: Comparison,
+    pub threshold: String,
+    pub hysteresis: String,
+}
+
+impl Draft {
+    /// Returns the edited rule, if its threshold and hysteresis are valid.
+    ///
+    /// An empty hysteresis counts as zero.
+    pub fn rule(&self) -> Option<Rule> {
+        let threshold: f64 = self
+            .threshold
+            .trim()
+            .parse()
+            .ok()
+            .filter(|threshold: &f64| threshold.is_finite())?;
+        let hysteresis: f64 = match self.hysteresis.trim() {
+            "" => 0.0,
+            hysteresis => hysteresis
+                .parse()
+                .ok()
+                .filter(|hysteresis: &f64| hysteresis.is_finite() && *hysteresis >= 0.0)?,
+        };
+
+        Some(Rule {
+            id: 0,
+            field: self.field,
+            instrument: self.scope.0.clone(),
+            comparison: self.comparison,
+            threshold,
+            hysteresis,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use iced::time::Duration;
+
+    fn rule(comparison: Comparison, threshold: f64, hysteresis: f64) -> Rule {
+        Rule {
+            id: 0,
+            field: Field::Spread,
+            instrument: None,
+            comparison,
+            threshold,
+            hysteresis,
+        }
+    }
+
+    fn snapshot(name: &str, spread: f64) -> StreamData {
+        StreamData {
+            name: name.to_string(),
+            currency: "BTC".to_string(),
+            spread,
+            ..StreamData::default()
+        }
+    }
+
+    /// The activity of every listed alert, in order of rule and instrument.
+    fn states(alerts: &Alerts) -> Vec<(bool, bool)> {
+        alerts
+            .alerts
+            .values()
+            .map(|alert| (alert.active, alert.acknowledged))
+            .collect()
+    }
+
+    #[test]
+    fn crossing_the_threshold_raises_once() {
+        let mut alerts = Alerts::default();
+        let _ = alerts.add_rule(rule(Comparison::Above, 10.0, 0.0));
+        let now = Instant::now();
+
+        assert_eq!(alerts.evaluate(&snapshot("A", 10.0), now), 0);
+        assert_eq!(alerts.len(), 0);
+
+        assert_eq!(alerts.evaluate(&snapshot("A", 11.0), now), 1);
+        assert_eq!(alerts.evaluate(&snapshot("A", 12.0), now), 0);
+        assert_eq!(states(&alerts), [(true, false)]);
+        assert_eq!(alerts.iter().next().unwrap().value, 12.0);
+    }
+
+    #[test]
+    fn hysteresis_delays_clearing() {
+        let mut alerts = Alerts::default();
+        let _ = alerts.add_rule(rule(Comparison::Above, 10.0, 2.0));
+        let now = Instant::now();
+
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        let _ = alerts.evaluate(&snapshot("A", 9.0), now);
+        assert_eq!(states(&alerts), [(true, false)]);
+
+        let _ = alerts.evaluate(&snapshot("A", 8.0), now);
+        assert_eq!(states(&alerts), [(false, false)]);
+
+        // Back above the threshold after clearing raises again.
+        let later = now + Duration::from_secs(1);
+        assert_eq!(alerts.evaluate(&snapshot("A", 10.5), later), 1);
+        assert_eq!(alerts.iter().next().unwrap().raised, later);
+    }
+
+    #[test]
+    fn below_clears_above_the_threshold_plus_hysteresis() {
+        let mut alerts = Alerts::default();
+        let _ = alerts.add_rule(rule(Comparison::Below, 10.0, 1.0));
+        let now = Instant::now();
+
+        assert_eq!(alerts.evaluate(&snapshot("A", 9.0), now), 1);
+        let _ = alerts.evaluate(&snapshot("A", 10.5), now);
+        assert_eq!(states(&alerts), [(true, false)]);
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        assert_eq!(states(&alerts), [(false, false)]);
+    }
+
+    #[test]
+    fn acknowledged_alerts_stay_until_cleared() {
+        let mut alerts = Alerts::default();
+        let id = alerts.add_rule(rule(Comparison::Above, 10.0, 0.0));
+        let now = Instant::now();
+        let instrument = Instrument::of(&snapshot("A", 0.0));
+
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        alerts.acknowledge(id, &instrument);
+        assert_eq!(states(&alerts), [(true, true)]);
+        assert_eq!(alerts.unacknowledged(), 0);
+        assert!(!alerts.is_raised(&[Field::Spread]));
+
+        let _ = alerts.evaluate(&snapshot("A", 9.0), now);
+        assert_eq!(alerts.len(), 0);
+    }
+
+    #[test]
+    fn cleared_alerts_stay_until_acknowledged() {
+        let mut alerts = Alerts::default();
+        let id = alerts.add_rule(rule(Comparison::Above, 10.0, 0.0));
+        let now = Instant::now();
+        let instrument = Instrument::of(&snapshot("A", 0.0));
+
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        let _ = alerts.evaluate(&snapshot("A", 9.0), now);
+        assert_eq!(states(&alerts), [(false, false)]);
+        assert!(alerts.is_raised(&[Field::Spread]));
+        assert!(!alerts.is_raised(&[Field::IndexPrice]));
+
+        alerts.acknowledge(id, &instrument);
+        assert_eq!(alerts.len(), 0);
+    }
+
+    #[test]
+    fn acknowledge_all_drops_cleared_alerts() {
+        let mut alerts = Alerts::default();
+        let _ = alerts.add_rule(rule(Comparison::Above, 10.0, 0.0));
+        let now = Instant::now();
+
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        let _ = alerts.evaluate(&snapshot("B", 11.0), now);
+        let _ = alerts.evaluate(&snapshot("B", 9.0), now);
+        assert_eq!(states(&alerts), [(true, false), (false, false)]);
+
+        alerts.acknowledge_all();
+        assert_eq!(states(&alerts), [(true, true)]);
+
+        // An acknowledged alert is dropped once it clears, and raising it again needs a new
+        // acknowledgement.
+        let _ = alerts.evaluate(&snapshot("A", 9.0), now);
+        let _ = alerts.evaluate(&snapshot("A", 11.0), now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts.unacknowledged(), 1);
+    }
+
+    #[test]
+    fn rules_only_watch_their_instrument() {
+        let mut alerts = Alerts::default();
+        let id = alerts.add_rule(Rule {
+            instrument: Some(Instrument::of(&snapshot("A", 0.0))),
+            ..rule(Comparison::Above, 10.0, 0.0)
+        });
+        let now = Instant::now();
+
+        assert_eq!(alerts.evaluate(&snapshot("B", 11.0), now), 0);
+        assert_eq!(alerts.evaluate(&snapshot("A", 11.0), now), 1);
+
+        alerts.remove_rule(id);
+        assert_eq!(alerts.len(), 0);
+    }
+}
@@ -0,0 +1,164 @@
+use iced::time::{Duration, Instant};
+use iced::{Point, Vector};
+
+/// The easing curve of the reordering animation of a [`Reorderable`].
+///
+/// [`Reorderable`]: crate::Reorderable
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and speeds up.
+    EaseIn,
+    /// Starts fast and slows down.
+    #[default]
+    EaseOut,
+    /// Starts slow, speeds up and slows down again.
+    EaseInOut,
+    /// A custom curve mapping the animation progress in `0.0..=1.0` to the
+    /// eased progress.
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Applies the [`Easing`] to the animation progress `t` in `0.0..=1.0`.
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::Custom(curve) => curve(t),
+        }
+    }
+}
+
+/// The animation settings of a [`Reorderable`].
+///
+/// [`Reorderable`]: crate::Reorderable
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Animation {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+/// The animated translation of a child element of a [`Reorderable`], tracked by key.
+///
+/// [`Reorderable`]: crate::Reorderable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Offset<Key> {
+    key: Key,
+    /// The layout position of the child element in the last frame.
+    position: Point,
+    from: Vector,
+    to: Vector,
+    start: Instant,
+    /// The translation of the child element in the last frame.
+    current: Vector,
+}
+
+/// The target of an animated [`Offset`] for the current frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Target<Key> {
+    pub key: Key,
+    /// The layout position of the child element.
+    pub position: Point,
+    /// The translation the child element should end up with.
+    pub offset: Vector,
+    /// Whether the child element should jump to `offset` instead of animating.
+    pub immediate: bool,
+}
+
+/// Advances the animated `offsets` towards the `targets` of the current frame.
+///
+/// Offsets are rebased when a child element changes its layout position, e.g. after it was
+/// dropped, so it keeps being drawn where it was and animates into its new slot from there.
+///
+/// Returns whether any offset is still animating.
+pub(crate) fn step<Key>(
+    offsets: &mut Vec<Offset<Key>>,
+    targets: impl IntoIterator<Item = Target<Key>>,
+    animation: Animation,
+    now: Instant,
+) -> bool
+where
+    Key: Copy + PartialEq,
+{
+    let mut animating = false;
+    let mut next = Vec::with_capacity(offsets.len());
+
+    for target in targets {
+        let mut offset = offsets
+            .iter()
+            .find(|offset| offset.key == target.key)
+            .copied()
+            .unwrap_or(Offset {
+                key: target.key,
+                position: target.position,
+                from: Vector::ZERO,
+                to: Vector::ZERO,
+                start: now,
+                current: Vector::ZERO,
+            });
+
+        if offset.position != target.position {
+            let shift = offset.position - target.position;
+            offset.from = offset.from + shift;
+            offset.to = offset.to + shift;
+            offset.current = offset.current + shift;
+            offset.position = target.position;
+        }
+
+        if target.immediate {
+            offset.from = target.offset;
+            offset.to = target.offset;
+        } else if offset.to != target.offset {
+            offset.from = offset.current;
+            offset.to = target.offset;
+            offset.start = now;
+        }
+
+        let elapsed = now.saturating_duration_since(offset.start).as_secs_f32();
+        let progress = if animation.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed / animation.duration.as_secs_f32()).min(1.0)
+        };
+        offset.current = offset.from + (offset.to - offset.from) * animation.easing.apply(progress);
+        animating |= progress < 1.0;
+
+        next.push(offset);
+    }
+
+    *offsets = next;
+    animating
+}
+
+/// Returns the order of the children of a [`Reorderable`] after moving the child elements at
+/// `indices` together to `drop_location`, as indices into the current order.
+///
+/// [`Reorderable`]: crate::Reorderable
+pub(crate) fn reordered(count: usize, indices: &[usize], drop_location: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..count).filter(|other| !indices.contains(other)).collect();
+    let before = indices.iter().filter(|index| **index < drop_location).count();
+    let new_index = drop_location.saturating_sub(before).min(order.len());
+    let _ = order.splice(new_index..new_index, indices.iter().copied());
+    order
+}
+
+/// Returns the current animated translation of the child element with the given key.
+pub(crate) fn current<Key>(offsets: &[Offset<Key>], key: Key) -> Vector
+where
+    Key: Copy + PartialEq,
+{
+    offsets
+        .iter()
+        .find(|offset| offset.key == key)
+        .map_or(Vector::ZERO, |offset| offset.current)
+}
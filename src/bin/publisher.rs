@@ -0,0 +1,510 @@
+//! Publishes synthetic perp summaries to a local NATS server, standing in for the production
+//! publisher of the `mm_perp_summary_derive` stream.
+//!
+//! Run it with `cargo run --bin publisher -- [options]` next to the dashboard (`cargo run --bin
+//! dashboard`). It connects with the same settings as the dashboard (see `nats.example.json`),
+//! creates the stream if it does not exist, and publishes one summary per instrument and
+//! interval on `<subject>.<name>`:
+//!
+//! | Option | Meaning | Default |
+//! |---|---|---|
+//! | `--subject <subject>` | Subject prefix | `mm_perp_summary` |
+//! | `--instruments <list>` | Comma-separated currencies | `BTC,ETH,SOL` |
+//! | `--interval <ms>` | Delay between rounds | `1000` |
+//! | `--seed <n>` | Seed of the random walks | `0` |
+//! | `--rounds <n>` | Stops after `n` rounds | never |
+//!
+//! The same seed always publishes the same summaries.
+//!
+//! It also answers the commands of the dashboard on `<command_subject>.*`: flattening zeroes
+//! the position of an instrument, pausing stops its fills until it is resumed, and a spread
+//! target replaces its random spread.
+use std::env;
+use std::fmt;
+use std::time::Duration;
+
+use async_nats::jetstream::{self, stream};
+use async_nats::Client;
+use futures::{FutureExt, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[allow(dead_code)]
+#[path = "../nats/config.rs"]
+mod config;
+#[allow(dead_code)]
+#[path = "../commands/protocol.rs"]
+mod protocol;
+#[allow(dead_code)]
+#[path = "../store.rs"]
+mod store;
+#[allow(dead_code)]
+#[path = "../stream_data.rs"]
+mod stream_data;
+
+use protocol::{Command, Reply};
+use stream_data::{SCHEMA_VERSION, StreamData};
+
+/// The largest relative move of an index price per round.
+const VOLATILITY: f64 = 0.002;
+
+pub fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            eprintln!(
+                "Usage: publisher [--subject <subject>] [--instruments <list>] [--interval <ms>] \
+                 [--seed <n>] [--rounds <n>]"
+            );
+            std::process::exit(2);
+        }
+    };
+    let config = match config::init() {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Invalid NATS configuration: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    if let Err(error) = runtime.block_on(publish(config, &options)) {
+        eprintln!("Publishing failed: {error}");
+        std::process::exit(1);
+    }
+}
+
+async fn publish(config: &config::Config, options: &Options) -> Result<(), async_nats::Error> {
+    let client = config.connect().await?;
+    let jetstream = jetstream::new(client.clone());
+    let mut commands = client.subscribe(format!("{}.*", config.command_subject)).await?.fuse();
+
+    let _ = jetstream
+        .get_or_create_stream(stream::Config {
+            name: config.stream.clone(),
+            subjects: vec![format!("{}.>", options.subject)],
+            max_messages_per_subject: 10_000,
+            ..Default::default()
+        })
+        .await?;
+
+    let mut markets: Vec<Market> = options
+        .instruments
+        .iter()
+        .enumerate()
+        .map(|(index, currency)| Market::new(currency, options.seed.wrapping_add(index as u64)))
+        .collect();
+
+    let mut interval = tokio::time::interval(options.interval);
+    let mut round = 0;
+
+    while options.rounds.is_none_or(|rounds| round < rounds) {
+        futures::select! {
+            _ = interval.tick().fuse() => {}
+            command = commands.select_next_some() => {
+                answer(&client, &mut markets, command).await?;
+                continue;
+            }
+        }
+
+        for market in &mut markets {
+            market.step();
+
+            let subject = format!("{}.{}", options.subject, market.data.name);
+            let payload = serde_json::to_vec(&market.data)?;
+            jetstream.publish(subject, payload.into()).await?.await?;
+        }
+
+        round += 1;
+        println!("Published round {round} for {} instruments", markets.len());
+    }
+
+    Ok(())
+}
+
+/// Applies a command of the dashboard to the markets and replies whether it succeeded.
+async fn answer(
+    client: &Client,
+    markets: &mut [Market],
+    message: async_nats::Message,
+) -> Result<(), async_nats::Error> {
+    let result = serde_json::from_slice::<Command>(&message.payload)
+        .map_err(|error| format!("invalid command: {error}"))
+        .and_then(|command| apply(markets, &command).map(|()| command));
+
+    match &result {
+        Ok(command) => println!("Applied command: {command}"),
+        Err(error) => println!("Rejected command on {}: {error}", message.subject),
+    }
+
+    if let Some(reply) = message.reply {
+        let payload = serde_json::to_vec(&Reply::from(result.map(|_| ())))?;
+        client.publish(reply, payload.into()).await?;
+    }
+
+    Ok(())
+}
+
+/// Applies a command to the market of its instrument.
+fn apply(markets: &mut [Market], command: &Command) -> Result<(), String> {
+    let instrument = command.instrument();
+    let market = markets
+        .iter_mut()
+        .find(|market| {
+            market.data.name == instrument.name && market.data.currency == instrument.currency
+        })
+        .ok_or_else(|| format!("unknown instrument {instrument}"))?;
+
+    match command {
+        Command::Flatten { .. } => market.flatten(),
+        Command::PauseQuoting { .. } => market.quoting = false,
+        Command::ResumeQuoting { .. } => market.quoting = true,
+        Command::SetSpreadTarget { spread, .. } => {
+            if !spread.is_finite() || *spread <= 0.0 {
+                return Err(format!("invalid spread {spread}"));
+            }
+            market.spread_target = Some(*spread);
+        }
+    }
+
+    Ok(())
+}
+
+/// The command-line options of the publisher.
+#[derive(Debug)]
+struct Options {
+    subject: String,
+    instruments: Vec<String>,
+    interval: Duration,
+    seed: u64,
+    rounds: Option<u64>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, Error> {
+        let mut options = Self {
+            subject: "mm_perp_summary".to_string(),
+            instruments: vec!["BTC".to_string(), "ETH".to_string(), "SOL".to_string()],
+            interval: Duration::from_secs(1),
+            seed: 0,
+            rounds: None,
+        };
+
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| Error(arg.clone()))?;
+            let invalid = || Error(format!("{arg} {value}"));
+
+            match arg.as_str() {
+                "--subject" => options.subject = value,
+                "--instruments" => {
+                    options.instruments = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|currency| !currency.is_empty())
+                        .map(str::to_uppercase)
+                        .collect();
+                    if options.instruments.is_empty() {
+                        return Err(invalid());
+                    }
+                }
+                "--interval" => {
+                    let millis: u64 = value.parse().map_err(|_| invalid())?;
+                    options.interval = Duration::from_millis(millis.max(1));
+                }
+                "--seed" => options.seed = value.parse().map_err(|_| invalid())?,
+                "--rounds" => options.rounds = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(Error(arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// An unknown command-line option, or one with an invalid or missing value.
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid option: {}", self.0)
+    }
+}
+
+/// The simulated book of one perp market.
+struct Market {
+    data: StreamData,
+    rng: StdRng,
+    /// Whether the market trades, until a command pauses it.
+    quoting: bool,
+    /// The spread set by a command, instead of a random one.
+    spread_target: Option<f64>,
+}
+
+impl Market {
+    fn new(currency: &str, seed: u64) -> Self {
+        let index_price = match currency {
+            "BTC" => 60_000.0,
+            "ETH" => 3_000.0,
+            "SOL" => 150.0,
+            _ => 100.0,
+        };
+
+        Self {
+            data: StreamData {
+                schema_version: SCHEMA_VERSION,
+                name: format!("{currency}-PERP"),
+                currency: currency.to_string(),
+                index_price,
+                long_ave_entry_price: index_price,
+                short_ave_entry_price: index_price,
+                ..StreamData::default()
+            },
+            rng: StdRng::seed_from_u64(seed),
+            quoting: true,
+            spread_target: None,
+        }
+    }
+
+    /// Moves the index price one step of its random walk and trades around it.
+    fn step(&mut self) {
+        let data = &mut self.data;
+        let rng = &mut self.rng;
+
+        data.index_price *= 1.0 + rng.random_range(-VOLATILITY..VOLATILITY);
+        data.spread = match self.spread_target {
+            Some(spread) => spread,
+            None => data.index_price * rng.random_range(0.0001..0.001),
+        };
+
+        if !self.quoting {
+            self.revalue();
+            return;
+        }
+
+        // A fill on either side, at a price within the spread.
+        let notional = rng.random_range(1_000.0..50_000.0);
+        let size = notional / data.index_price;
+        let price = data.index_price + rng.random_range(-0.5..0.5) * data.spread;
+        let realized = if rng.random_bool(0.5) {
+            let realized = close(&mut data.short_size, data.short_ave_entry_price - price, size);
+            open(&mut data.long_size, &mut data.long_ave_entry_price, price, size);
+            realized
+        } else {
+            let realized = close(&mut data.long_size, price - data.long_ave_entry_price, size);
+            open(&mut data.short_size, &mut data.short_ave_entry_price, price, size);
+            realized
+        };
+
+        let (maker, taker) = if rng.random_bool(0.7) { (notional, 0.0) } else { (0.0, notional) };
+        data.dtd_volume_maker += maker;
+        data.wtd_volume_maker += maker;
+        data.mtd_volume_maker += maker;
+        data.dtd_volume_taker += taker;
+        data.wtd_volume_taker += taker;
+        data.mtd_volume_taker += taker;
+
+        data.dtd_pnl += realized;
+        data.wtd_pnl += realized;
+        data.mtd_pnl += realized;
+
+        self.revalue();
+    }
+
+    /// Closes both sides at the index price, realizing their PnL.
+    fn flatten(&mut self) {
+        let data = &mut self.data;
+
+        data.dtd_pnl += data.unrealized_pnl;
+        data.wtd_pnl += data.unrealized_pnl;
+        data.mtd_pnl += data.unrealized_pnl;
+        data.long_size = 0.0;
+        data.short_size = 0.0;
+
+        self.revalue();
+    }
+
+    /// Values the position at the index price.
+    fn revalue(&mut self) {
+        let data = &mut self.data;
+
+        data.long_usd_notional = data.long_size * data.index_price;
+        data.short_usd_notional = data.short_size * data.index_price;
+        data.unrealized_pnl = data.long_size * (data.index_price - data.long_ave_entry_price)
+            + data.short_size * (data.short_ave_entry_price - data.index_price);
+    }
+}
+
+/// Adds a fill to a side, moving its average entry price.
+fn open(position: &mut f64, entry_price: &mut f64, price: f64, size: f64) {
+    *entry_price = (*entry_price * *position + price * size) / (*position + size);
+    *position += size;
+}
+
+/// Reduces the opposite side by up to half a fill and returns the realized PnL.
+fn close(position: &mut f64, profit_per_unit: f64, size: f64) -> f64 {
+    let closed = position.min(size / 2.0);
+    *position -= closed;
+    closed * profit_per_unit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, Error> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn rejected(args: &[&str]) -> String {
+        parse(args).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn no_options_are_the_defaults() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options.subject, "mm_perp_summary");
+        assert_eq!(options.instruments, ["BTC", "ETH", "SOL"]);
+        assert_eq!(options.interval, Duration::from_secs(1));
+        assert_eq!(options.seed, 0);
+        assert_eq!(options.rounds, None);
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse(&[
+            "--subject", "test", "--instruments", "btc, doge,", "--interval", "250",
+            "--seed", "42", "--rounds", "3",
+        ])
+        .unwrap();
+
+        assert_eq!(options.subject, "test");
+        assert_eq!(options.instruments, ["BTC", "DOGE"]);
+        assert_eq!(options.interval, Duration::from_millis(250));
+        assert_eq!(options.seed, 42);
+        assert_eq!(options.rounds, Some(3));
+    }
+
+    #[test]
+    fn missing_values_are_rejected() {
+        assert_eq!(rejected(&["--seed"]), "invalid option: --seed");
+        assert_eq!(rejected(&["--subject", "test", "--rounds"]), "invalid option: --rounds");
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert_eq!(rejected(&["--interval", "fast"]), "invalid option: --interval fast");
+        assert_eq!(rejected(&["--seed", "-1"]), "invalid option: --seed -1");
+        assert_eq!(rejected(&["--rounds", "1.5"]), "invalid option: --rounds 1.5");
+        assert_eq!(rejected(&["--instruments", " , "]), "invalid option: --instruments  , ");
+        assert_eq!(rejected(&["--verbose", "1"]), "invalid option: --verbose");
+    }
+
+    /// The summaries of `rounds` steps of a market.
+    fn walk(currency: &str, seed: u64, rounds: usize) -> Vec<StreamData> {
+        let mut market = Market::new(currency, seed);
+
+        (0..rounds)
+            .map(|_| {
+                market.step();
+                market.data.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_publishes_the_same_summaries() {
+        assert_eq!(walk("BTC", 7, 100), walk("BTC", 7, 100));
+        assert_ne!(walk("BTC", 7, 100), walk("BTC", 8, 100));
+    }
+
+    #[test]
+    fn positions_stay_consistent() {
+        for data in walk("ETH", 1, 1_000) {
+            assert!(data.index_price > 0.0);
+            assert!(data.long_size >= 0.0 && data.short_size >= 0.0);
+            assert!(data.spread > 0.0 && data.spread < data.index_price);
+            assert_eq!(data.long_usd_notional, data.long_size * data.index_price);
+        }
+    }
+
+    fn command(json: &str) -> Command {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn btc() -> Vec<Market> {
+        let mut markets = vec![Market::new("BTC", 3)];
+        (0..50).for_each(|_| markets[0].step());
+        markets
+    }
+
+    #[test]
+    fn flatten_closes_the_position() {
+        let mut markets = btc();
+        let pnl = markets[0].data.dtd_pnl + markets[0].data.unrealized_pnl;
+
+        let flatten = r#"{"command":"flatten","instrument":{"name":"BTC-PERP","currency":"BTC"}}"#;
+        assert_eq!(apply(&mut markets, &command(flatten)), Ok(()));
+
+        let data = &markets[0].data;
+        assert_eq!((data.long_size, data.short_size), (0.0, 0.0));
+        assert_eq!(data.unrealized_pnl, 0.0);
+        assert_eq!(data.dtd_pnl, pnl);
+    }
+
+    #[test]
+    fn paused_markets_do_not_trade() {
+        let mut markets = btc();
+        let pause =
+            r#"{"command":"pause_quoting","instrument":{"name":"BTC-PERP","currency":"BTC"}}"#;
+        assert_eq!(apply(&mut markets, &command(pause)), Ok(()));
+
+        let volume = markets[0].data.dtd_volume_maker + markets[0].data.dtd_volume_taker;
+        (0..10).for_each(|_| markets[0].step());
+        assert_eq!(markets[0].data.dtd_volume_maker + markets[0].data.dtd_volume_taker, volume);
+
+        let resume =
+            r#"{"command":"resume_quoting","instrument":{"name":"BTC-PERP","currency":"BTC"}}"#;
+        assert_eq!(apply(&mut markets, &command(resume)), Ok(()));
+        markets[0].step();
+        assert!(markets[0].data.dtd_volume_maker + markets[0].data.dtd_volume_taker > volume);
+    }
+
+    #[test]
+    fn spread_target_replaces_the_random_spread() {
+        let mut markets = btc();
+        let target = r#"{"command":"set_spread_target",
+            "instrument":{"name":"BTC-PERP","currency":"BTC"},"spread":12.5}"#;
+        assert_eq!(apply(&mut markets, &command(target)), Ok(()));
+
+        markets[0].step();
+        assert_eq!(markets[0].data.spread, 12.5);
+    }
+
+    #[test]
+    fn commands_for_other_instruments_are_rejected() {
+        let mut markets = btc();
+        let flatten = r#"{"command":"flatten","instrument":{"name":"ETH-PERP","currency":"ETH"}}"#;
+
+        assert_eq!(
+            apply(&mut markets, &command(flatten)),
+            Err("unknown instrument ETH-PERP (ETH)".to_string())
+        );
+    }
+
+    #[test]
+    fn replies_carry_the_error() {
+        let ok = serde_json::to_string(&Reply::from(Ok(()))).unwrap();
+        let failed = serde_json::to_string(&Reply::from(Err("no".to_string()))).unwrap();
+
+        assert_eq!(ok, r#"{"ok":true}"#);
+        assert_eq!(failed, r#"{"ok":false,"error":"no"}"#);
+        assert_eq!(
+            Result::<(), String>::from(serde_json::from_str::<Reply>(&failed).unwrap()),
+            Err("no".to_string())
+        );
+    }
+}
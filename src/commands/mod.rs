@@ -0,0 +1,184 @@
+//! Commands sent from the dashboard to the publisher over NATS request/reply.
+//!
+//! Every [`Command`] is sent as JSON on `<command_subject>.<name>` and waits for a reply until
+//! the `request_timeout_ms` of the [`config`]. The publisher answers with a [`Reply`].
+mod protocol;
+
+use std::time::Duration;
+
+use async_nats::{Client, RequestErrorKind};
+use futures::sink::SinkExt;
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use iced::futures::channel::mpsc;
+use iced::stream;
+use iced::time::Instant;
+use crate::nats::config;
+use crate::store::Instrument;
+use crate::ws_handler;
+use crate::Message;
+
+pub use protocol::{Action, Command, Reply};
+
+/// The delay before connecting again after the command connection failed.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+pub type RequestId = u64;
+
+/// A [`Command`] being composed, with its numbers as typed.
+#[derive(Debug, Clone, Default)]
+pub struct Draft {
+    pub action: Action,
+    pub instrument: Option<Instrument>,
+    pub spread: String,
+}
+
+impl Draft {
+    /// Returns the composed command, if it names an instrument and, for a spread target, a
+    /// valid spread.
+    pub fn command(&self) -> Option<Command> {
+        let instrument = self.instrument.clone()?;
+
+        Some(match self.action {
+            Action::Flatten => Command::Flatten { instrument },
+            Action::PauseQuoting => Command::PauseQuoting { instrument },
+            Action::ResumeQuoting => Command::ResumeQuoting { instrument },
+            Action::SetSpreadTarget => Command::SetSpreadTarget {
+                instrument,
+                spread: self
+                    .spread
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|spread: &f64| spread.is_finite() && *spread > 0.0)?,
+            },
+        })
+    }
+}
+
+/// Sends [`Command`]s to the publisher.
+#[derive(Debug, Clone)]
+pub struct Connection(mpsc::Sender<(RequestId, Command)>);
+
+impl Connection {
+    pub fn send(&mut self, id: RequestId, command: Command) -> Result<(), String> {
+        self.0.try_send((id, command)).map_err(|error| error.to_string())
+    }
+}
+
+/// A change of the command channel.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Connected(Connection),
+    Disconnected(String),
+    /// Whether the publisher applied a request, or why it did not.
+    Replied(RequestId, Result<(), String>),
+}
+
+/// Streams the [`Event`]s of the command channel.
+///
+/// Requests are sent concurrently, so a publisher that does not answer one command does not
+/// hold up the next. The client reconnects on its own and reports it through
+/// [`ws_handler::client_events`], so the status follows the actual connection.
+pub fn connect() -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        let config = config::get();
+
+        loop {
+            let (sender, mut events) = mpsc::unbounded();
+            let client = match config
+                .connect_with(|options| ws_handler::client_events(options, sender))
+                .await
+            {
+                Ok(client) => client,
+                Err(error) => {
+                    let _ = output
+                        .send(Message::Commands(Event::Disconnected(error.to_string())))
+                        .await;
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            let (sender, mut requests) = mpsc::channel(10);
+            let connection = Connection(sender);
+            let _ = output.send(Message::Commands(Event::Connected(connection.clone()))).await;
+
+            let mut pending = FuturesUnordered::new();
+            let mut closed = false;
+
+            while !closed {
+                let event = futures::select! {
+                    (id, command) = requests.select_next_some() => {
+                        pending.push(request_reply(&client, id, command));
+                        continue;
+                    }
+                    (id, result) = pending.select_next_some() => Event::Replied(id, result),
+                    event = events.select_next_some() => match event {
+                        ws_handler::Event::Connected => Event::Connected(connection.clone()),
+                        ws_handler::Event::Disconnected { reason } => Event::Disconnected(reason),
+                        // The client will not reconnect, so it is replaced.
+                        ws_handler::Event::Closed => {
+                            closed = true;
+                            Event::Disconnected("connection closed".to_string())
+                        }
+                        _ => continue,
+                    },
+                };
+
+                if output.send(Message::Commands(event)).await.is_err() {
+                    return;
+                }
+            }
+
+            // The requests sent before the close fail on their own.
+            while let Some((id, result)) = pending.next().await {
+                if output.send(Message::Commands(Event::Replied(id, result))).await.is_err() {
+                    return;
+                }
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    })
+}
+
+async fn request_reply(
+    client: &Client,
+    id: RequestId,
+    command: Command,
+) -> (RequestId, Result<(), String>) {
+    let config = config::get();
+    let subject = format!("{}.{}", config.command_subject, command.action().name());
+
+    let payload = match serde_json::to_vec(&command) {
+        Ok(payload) => payload,
+        Err(error) => return (id, Err(format!("cannot encode command: {error}"))),
+    };
+    let request = async_nats::Request::new()
+        .payload(payload.into())
+        .timeout(Some(config.request_timeout()));
+
+    let result = match client.send_request(subject, request).await {
+        Ok(reply) => match serde_json::from_slice::<Reply>(&reply.payload) {
+            Ok(reply) => reply.into(),
+            Err(error) => Err(format!("invalid reply: {error}")),
+        },
+        Err(error) => Err(match error.kind() {
+            RequestErrorKind::TimedOut => {
+                format!("no reply within {}ms", config.request_timeout_ms)
+            }
+            RequestErrorKind::NoResponders => "nobody handles this command".to_string(),
+            RequestErrorKind::Other => error.to_string(),
+        }),
+    };
+
+    (id, result)
+}
+
+/// A sent [`Command`] and its reply, once there is one.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub id: RequestId,
+    pub command: Command,
+    pub sent: Instant,
+    pub reply: Option<Result<(), String>>,
+}
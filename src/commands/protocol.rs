@@ -0,0 +1,117 @@
+//! The messages of the command channel, shared by the dashboard and the publisher.
+//!
+//! The publisher answers every [`Command`] with a [`Reply`], e.g. `{"ok":true}` or
+//! `{"ok":false,"error":"unknown instrument"}`.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::Instrument;
+
+/// What a [`Command`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Action {
+    #[default]
+    Flatten,
+    PauseQuoting,
+    ResumeQuoting,
+    SetSpreadTarget,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [
+        Action::Flatten,
+        Action::PauseQuoting,
+        Action::ResumeQuoting,
+        Action::SetSpreadTarget,
+    ];
+
+    /// The name of the action in the subject of its commands.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Flatten => "flatten",
+            Action::PauseQuoting => "pause_quoting",
+            Action::ResumeQuoting => "resume_quoting",
+            Action::SetSpreadTarget => "set_spread_target",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Action::Flatten => "Flatten position",
+            Action::PauseQuoting => "Pause quoting",
+            Action::ResumeQuoting => "Resume quoting",
+            Action::SetSpreadTarget => "Set spread target",
+        })
+    }
+}
+
+/// An instruction for the publisher about one instrument.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Command {
+    Flatten { instrument: Instrument },
+    PauseQuoting { instrument: Instrument },
+    ResumeQuoting { instrument: Instrument },
+    SetSpreadTarget { instrument: Instrument, spread: f64 },
+}
+
+impl Command {
+    pub fn action(&self) -> Action {
+        match self {
+            Command::Flatten { .. } => Action::Flatten,
+            Command::PauseQuoting { .. } => Action::PauseQuoting,
+            Command::ResumeQuoting { .. } => Action::ResumeQuoting,
+            Command::SetSpreadTarget { .. } => Action::SetSpreadTarget,
+        }
+    }
+
+    pub fn instrument(&self) -> &Instrument {
+        match self {
+            Command::Flatten { instrument }
+            | Command::PauseQuoting { instrument }
+            | Command::ResumeQuoting { instrument }
+            | Command::SetSpreadTarget { instrument, .. } => instrument,
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::SetSpreadTarget { instrument, spread } => {
+                write!(f, "Set spread target of {instrument} to {spread}")
+            }
+            command => write!(f, "{} of {}", command.action(), command.instrument()),
+        }
+    }
+}
+
+/// The answer of the publisher to a [`Command`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reply {
+    pub ok: bool,
+    /// Why the command failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<Result<(), String>> for Reply {
+    fn from(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self { ok: true, error: None },
+            Err(error) => Self { ok: false, error: Some(error) },
+        }
+    }
+}
+
+impl From<Reply> for Result<(), String> {
+    fn from(reply: Reply) -> Self {
+        match reply {
+            Reply { ok: true, .. } => Ok(()),
+            Reply { error, .. } => Err(error.unwrap_or_else(|| "rejected".to_string())),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use iced::advanced::layout;
+use iced::advanced::widget::Tree;
+use iced::advanced::Layout;
+use iced::alignment::Alignment;
+use iced::{Element, Length, Padding, Point, Rectangle, Size, Vector};
+
+/// The strategy a [`Reorderable`] uses to lay out its children and to map
+/// cursor positions to drop locations.
+///
+/// [`Reorderable`]: crate::Reorderable
+pub trait Direction: Copy + Default {
+    /// Lays out the children of a [`Reorderable`].
+    ///
+    /// [`Reorderable`]: crate::Reorderable
+    #[allow(clippy::too_many_arguments)]
+    fn layout<Message, Theme, Renderer>(
+        &self,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        width: Length,
+        height: Length,
+        padding: Padding,
+        spacing: f32,
+        align: Alignment,
+        children: &[Element<'_, Message, Theme, Renderer>],
+        trees: &mut [Tree],
+    ) -> layout::Node
+    where
+        Renderer: iced::advanced::Renderer;
+
+    /// Returns the index of the drop location among the children at given `position`.
+    fn drop_location(&self, layout: &Layout<'_>, position: Point) -> usize;
+
+    /// Returns the start and end points of the drop location marker line.
+    fn drop_marker(
+        &self,
+        layout: &Layout<'_>,
+        padding: Padding,
+        spacing: f32,
+        drop_location: usize,
+    ) -> Option<(Point, Point)>;
+
+    /// Constrains a dragged `position` to the main axis, keeping the cross axis
+    /// coordinate of `origin`.
+    fn lock_to_axis(&self, position: Point, origin: Point) -> Point;
+
+    /// Returns the translation of every child element that previews the children laid out
+    /// in the given `order`, as indices into the current order.
+    ///
+    /// By default, every child element moves into the slot of the child element it replaces.
+    fn preview(&self, layout: &Layout<'_>, _spacing: f32, order: &[usize]) -> Vec<Vector> {
+        let slots: Vec<Point> = layout.children().map(|item| item.bounds().position()).collect();
+        let mut offsets = vec![Vector::ZERO; slots.len()];
+        for (slot, &index) in order.iter().enumerate() {
+            offsets[index] = slots[slot] - slots[index];
+        }
+        offsets
+    }
+}
+
+/// Children are laid out vertically, as in a [`Column`].
+///
+/// [`Column`]: crate::Column
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Vertical;
+
+/// Children are laid out horizontally, as in a [`Row`].
+///
+/// [`Row`]: crate::Row
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Horizontal;
+
+/// Children are laid out horizontally and wrap into new lines when running
+/// out of space, as in a [`Grid`].
+///
+/// [`Grid`]: crate::Grid
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Wrapping {
+    /// The vertical spacing between lines. Defaults to the horizontal spacing.
+    pub line_spacing: Option<f32>,
+}
+
+impl Direction for Vertical {
+    fn layout<Message, Theme, Renderer>(
+        &self,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        width: Length,
+        height: Length,
+        padding: Padding,
+        spacing: f32,
+        align: Alignment,
+        children: &[Element<'_, Message, Theme, Renderer>],
+        trees: &mut [Tree],
+    ) -> layout::Node
+    where
+        Renderer: iced::advanced::Renderer,
+    {
+        layout::flex::resolve(
+            layout::flex::Axis::Vertical,
+            renderer,
+            limits,
+            width,
+            height,
+            padding,
+            spacing,
+            align,
+            children,
+            trees,
+        )
+    }
+
+    fn drop_location(&self, layout: &Layout<'_>, position: Point) -> usize {
+        let mut index = 0;
+        for item_layout in layout.children() {
+            if position.y < item_layout.bounds().center_y() {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+
+    fn drop_marker(
+        &self,
+        layout: &Layout<'_>,
+        padding: Padding,
+        spacing: f32,
+        drop_location: usize,
+    ) -> Option<(Point, Point)> {
+        let line_y = if layout.children().count() == 0 {
+            return None;
+        } else if drop_location < layout.children().count() {
+            let child_bounds_below = layout.children().nth(drop_location).unwrap().bounds();
+            child_bounds_below.y - spacing * 0.5
+        } else {
+            let last_child_bounds = layout.children().last().unwrap().bounds();
+            last_child_bounds.y + last_child_bounds.height + spacing * 0.5
+        };
+        let bounds = layout.bounds();
+        Some((
+            Point::new(bounds.x + padding.left, line_y),
+            Point::new(bounds.x + bounds.width - padding.right, line_y),
+        ))
+    }
+
+    fn lock_to_axis(&self, position: Point, origin: Point) -> Point {
+        Point::new(origin.x, position.y)
+    }
+
+    fn preview(&self, layout: &Layout<'_>, spacing: f32, order: &[usize]) -> Vec<Vector> {
+        let bounds: Vec<Rectangle> = layout.children().map(|item| item.bounds()).collect();
+        let mut offsets = vec![Vector::ZERO; bounds.len()];
+        let mut y = bounds.first().map_or(0.0, |first| first.y);
+        for &index in order {
+            offsets[index] = Vector::new(0.0, y - bounds[index].y);
+            y += bounds[index].height + spacing;
+        }
+        offsets
+    }
+}
+
+impl Direction for Horizontal {
+    fn layout<Message, Theme, Renderer>(
+        &self,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        width: Length,
+        height: Length,
+        padding: Padding,
+        spacing: f32,
+        align: Alignment,
+        children: &[Element<'_, Message, Theme, Renderer>],
+        trees: &mut [Tree],
+    ) -> layout::Node
+    where
+        Renderer: iced::advanced::Renderer,
+    {
+        layout::flex::resolve(
+            layout::flex::Axis::Horizontal,
+            renderer,
+            limits,
+            width,
+            height,
+            padding,
+            spacing,
+            align,
+            children,
+            trees,
+        )
+    }
+
+    fn drop_location(&self, layout: &Layout<'_>, position: Point) -> usize {
+        let mut index = 0;
+        for item_layout in layout.children() {
+            if position.x < item_layout.bounds().center_x() {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+
+    fn drop_marker(
+        &self,
+        layout: &Layout<'_>,
+        padding: Padding,
+        spacing: f32,
+        drop_location: usize,
+    ) -> Option<(Point, Point)> {
+        let line_x = if layout.children().count() == 0 {
+            return None;
+        } else if drop_location < layout.children().count() {
+            let child_bounds_after = layout.children().nth(drop_location).unwrap().bounds();
+            child_bounds_after.x - spacing * 0.5
+        } else {
+            let last_child_bounds = layout.children().last().unwrap().bounds();
+            last_child_bounds.x + last_child_bounds.width + spacing * 0.5
+        };
+        let bounds = layout.bounds();
+        Some((
+            Point::new(line_x, bounds.y + padding.top),
+            Point::new(line_x, bounds.y + bounds.height - padding.bottom),
+        ))
+    }
+
+    fn lock_to_axis(&self, position: Point, origin: Point) -> Point {
+        Point::new(position.x, origin.y)
+    }
+
+    fn preview(&self, layout: &Layout<'_>, spacing: f32, order: &[usize]) -> Vec<Vector> {
+        let bounds: Vec<Rectangle> = layout.children().map(|item| item.bounds()).collect();
+        let mut offsets = vec![Vector::ZERO; bounds.len()];
+        let mut x = bounds.first().map_or(0.0, |first| first.x);
+        for &index in order {
+            offsets[index] = Vector::new(x - bounds[index].x, 0.0);
+            x += bounds[index].width + spacing;
+        }
+        offsets
+    }
+}
+
+impl Direction for Wrapping {
+    fn layout<Message, Theme, Renderer>(
+        &self,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        width: Length,
+        height: Length,
+        padding: Padding,
+        spacing: f32,
+        align: Alignment,
+        children: &[Element<'_, Message, Theme, Renderer>],
+        trees: &mut [Tree],
+    ) -> layout::Node
+    where
+        Renderer: iced::advanced::Renderer,
+    {
+        let limits = limits.width(width).height(height).shrink(padding);
+        let child_limits = limits.loose();
+        let line_spacing = self.line_spacing.unwrap_or(spacing);
+        let max_width = limits.max().width;
+
+        let align_factor = match align {
+            Alignment::Start => 0.0,
+            Alignment::Center => 2.0,
+            Alignment::End => 1.0,
+        };
+        let align_line = |line: std::ops::Range<usize>, line_height: f32, nodes: &mut [layout::Node]| {
+            if align_factor != 0.0 {
+                for node in &mut nodes[line] {
+                    let offset = (line_height - node.size().height) / align_factor;
+                    node.translate_mut(Vector::new(0.0, offset));
+                }
+            }
+        };
+
+        let mut nodes: Vec<layout::Node> = Vec::with_capacity(children.len());
+        let mut intrinsic_size = Size::ZERO;
+        let mut line_start = 0;
+        let mut line_height: f32 = 0.0;
+        let mut x = 0.0;
+        let mut y = 0.0;
+
+        for (index, (child, tree)) in children.iter().zip(trees.iter_mut()).enumerate() {
+            let node = child.as_widget().layout(tree, renderer, &child_limits);
+            let child_size = node.size();
+
+            if x != 0.0 && x + child_size.width > max_width {
+                intrinsic_size.width = intrinsic_size.width.max(x - spacing);
+                align_line(line_start..index, line_height, &mut nodes);
+
+                y += line_height + line_spacing;
+                x = 0.0;
+                line_start = index;
+                line_height = 0.0;
+            }
+
+            line_height = line_height.max(child_size.height);
+            nodes.push(node.move_to((x + padding.left, y + padding.top)));
+            x += child_size.width + spacing;
+        }
+
+        if x != 0.0 {
+            intrinsic_size.width = intrinsic_size.width.max(x - spacing);
+        }
+        intrinsic_size.height = y + line_height;
+        align_line(line_start..nodes.len(), line_height, &mut nodes);
+
+        let size = limits.resolve(width, height, intrinsic_size);
+
+        layout::Node::with_children(size.expand(padding), nodes)
+    }
+
+    fn drop_location(&self, layout: &Layout<'_>, position: Point) -> usize {
+        let mut index = 0;
+        for item_layout in layout.children() {
+            let bounds = item_layout.bounds();
+            if position.y < bounds.y
+                || (position.y < bounds.y + bounds.height && position.x < bounds.center_x())
+            {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+
+    fn drop_marker(
+        &self,
+        layout: &Layout<'_>,
+        padding: Padding,
+        spacing: f32,
+        drop_location: usize,
+    ) -> Option<(Point, Point)> {
+        let (line_x, child_bounds) = if layout.children().count() == 0 {
+            return None;
+        } else if drop_location < layout.children().count() {
+            let child_bounds_after = layout.children().nth(drop_location).unwrap().bounds();
+            (child_bounds_after.x - spacing * 0.5, child_bounds_after)
+        } else {
+            let last_child_bounds = layout.children().last().unwrap().bounds();
+            (
+                last_child_bounds.x + last_child_bounds.width + spacing * 0.5,
+                last_child_bounds,
+            )
+        };
+        // Markers at the start or end of a line stay inside the padding.
+        let bounds = layout.bounds();
+        let line_x = line_x
+            .min(bounds.x + bounds.width - padding.right)
+            .max(bounds.x + padding.left);
+        Some((
+            Point::new(line_x, child_bounds.y),
+            Point::new(line_x, child_bounds.y + child_bounds.height),
+        ))
+    }
+
+    fn lock_to_axis(&self, position: Point, _origin: Point) -> Point {
+        position
+    }
+}
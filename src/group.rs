@@ -0,0 +1,107 @@
+use iced::advanced::widget;
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// A set of [`Reorderable`] containers that can exchange their children by
+/// dragging and dropping them from one container into another.
+///
+/// Every container in a [`Group`] needs a unique [`widget::Id`] and the same
+/// `Key` type.
+///
+/// [`Reorderable`]: crate::Reorderable
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Group(Cow<'static, str>);
+
+impl Group {
+    /// Creates a new [`Group`] with the given name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+}
+
+impl From<&'static str> for Group {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+/// Child elements being dragged across the containers of a [`Group`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Transfer<Key> {
+    /// The keys of the dragged child elements, in their order in the source container.
+    pub keys: Vec<Key>,
+    /// The container the child elements were grabbed from.
+    pub source: widget::Id,
+    /// The container under the cursor, other than the source, and the drop location in it.
+    pub target: Option<(widget::Id, usize)>,
+}
+
+thread_local! {
+    /// The active [`Transfer`] of every [`Group`].
+    ///
+    /// Containers of a [`Group`] live in separate widget trees, so they share the
+    /// in-flight drag through this registry instead of their tree state.
+    static TRANSFERS: RefCell<HashMap<Group, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the active [`Transfer`] of a [`Group`], if any.
+pub(crate) fn transfer<Key>(group: &Group) -> Option<Transfer<Key>>
+where
+    Key: Clone + 'static,
+{
+    TRANSFERS.with_borrow(|transfers| {
+        transfers
+            .get(group)
+            .and_then(|transfer| transfer.downcast_ref::<Transfer<Key>>())
+            .cloned()
+    })
+}
+
+/// Starts a [`Transfer`] of the child elements with the given keys out of `source`.
+pub(crate) fn start<Key>(group: &Group, keys: Vec<Key>, source: widget::Id)
+where
+    Key: 'static,
+{
+    let transfer = Transfer {
+        keys,
+        source,
+        target: None,
+    };
+    TRANSFERS.with_borrow_mut(|transfers| {
+        let _ = transfers.insert(group.clone(), Box::new(transfer));
+    });
+}
+
+/// Sets the target container of the active [`Transfer`] of a [`Group`].
+///
+/// Passing `None` only clears the target if it is currently `id`.
+pub(crate) fn set_target<Key>(group: &Group, id: &widget::Id, drop_location: Option<usize>)
+where
+    Key: 'static,
+{
+    TRANSFERS.with_borrow_mut(|transfers| {
+        if let Some(transfer) = transfers
+            .get_mut(group)
+            .and_then(|transfer| transfer.downcast_mut::<Transfer<Key>>())
+        {
+            match drop_location {
+                Some(drop_location) => transfer.target = Some((id.clone(), drop_location)),
+                None => {
+                    if transfer.target.as_ref().is_some_and(|(target, _)| target == id) {
+                        transfer.target = None;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Ends the active [`Transfer`] of a [`Group`].
+pub(crate) fn finish(group: &Group) {
+    TRANSFERS.with_borrow_mut(|transfers| {
+        let _ = transfers.remove(group);
+    });
+}
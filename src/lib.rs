@@ -1,19 +1,34 @@
+mod animation;
+mod direction;
+mod group;
+mod list;
+#[cfg(test)]
+mod tests;
+
+pub use animation::Easing;
+pub use direction::{Direction, Horizontal, Vertical, Wrapping};
+pub use group::Group;
+pub use list::List;
+
 use iced::advanced::layout;
 use iced::advanced::overlay;
 use iced::advanced::renderer;
-use iced::advanced::widget::{Operation, Tree, Widget};
+use iced::advanced::widget::{self, operation, Operation, Tree, Widget};
 use iced::advanced::Clipboard;
 use iced::advanced::Layout;
 use iced::advanced::Shell;
 use iced::alignment::{self, Alignment};
 use iced::border::Radius;
+use iced::keyboard;
 use iced::mouse;
 use iced::touch;
+use iced::window;
 use iced::Border;
 use iced::Color;
 use iced::Event;
 use iced::Point;
 use iced::Theme;
+use iced::time::{Duration, Instant};
 use iced::{Element, Length, Padding, Pixels, Rectangle, Size, Vector};
 
 /// A container that distributes its contents vertically and allows dragging
@@ -21,50 +36,74 @@ use iced::{Element, Length, Padding, Pixels, Rectangle, Size, Vector};
 ///
 /// # Example
 /// ```no_run
-/// use super::Column;
+/// use iced::widget::text;
+/// use iced::Element;
+/// use iced_playground::{Column, List};
 ///
-/// let mut data = vec![
-///    "First item",
-///    "Second item",
-///    "Third item",
-///    "Fourth item",
-/// ];
+/// struct State {
+///     items: List<usize, &'static str>,
+/// }
 ///
 /// #[derive(Debug, Clone)]
 /// enum Message {
 ///     Dropped(usize, usize),
 /// }
 ///
-/// fn move_on_drop(&mut data: Vec<>, msg: Message) {
-///     if let Message::Dropped(src_index, dst_index) = msg {
-///         if src_index > self.items.len() || src_index == dst_index || src_index + 1 == dst_index {
-///             return;
-///         }
-///         if dst_index > src_index {
-///             dst_index -= 1;
-///         }
-///         let slot = self.items.remove(src_index);
-///         if dst_index < self.items.len() {
-///             self.items.insert(dst_index, slot);
-///         } else {
-///             self.items.push(slot);
+/// fn new() -> State {
+///     State {
+///         items: List::from_iter([
+///             (0, "First item"),
+///             (1, "Second item"),
+///             (2, "Third item"),
+///             (3, "Fourth item"),
+///         ]),
+///     }
+/// }
+///
+/// fn update(state: &mut State, message: Message) {
+///     match message {
+///         Message::Dropped(key, drop_location) => {
+///             let _ = state.items.drop_at(key, drop_location);
 ///         }
 ///     }
 /// }
 ///
 /// fn view(state: &State) -> Element<'_, Message> {
-///     Column::with_children(data.iter().enumerate())
-///     .on_drop(Message::Dropped)
-///     .into()
+///     Column::with_children(state.items.iter().map(|(key, item)| (key, text(*item).into())))
+///         .on_drop(Message::Dropped)
+///         .into()
 /// }
 /// ```
+pub type Column<'a, Key, Message, Theme, Renderer> =
+    Reorderable<'a, Key, Message, Theme, Renderer, Vertical>;
+
+/// A container that distributes its contents horizontally and allows dragging
+/// and dropping its keyed children.
+///
+/// It shares the dragging behavior and callbacks of a [`Column`].
+pub type Row<'a, Key, Message, Theme, Renderer> =
+    Reorderable<'a, Key, Message, Theme, Renderer, Horizontal>;
+
+/// A container that distributes its contents horizontally, wrapping them into
+/// new lines when running out of space, and allows dragging and dropping its
+/// keyed children.
+///
+/// It shares the dragging behavior and callbacks of a [`Column`].
+pub type Grid<'a, Key, Message, Theme, Renderer> =
+    Reorderable<'a, Key, Message, Theme, Renderer, Wrapping>;
+
+/// A container that distributes its contents along a [`Direction`] and allows
+/// dragging and dropping its keyed children.
+///
+/// Use one of [`Column`], [`Row`] or [`Grid`] instead of naming this type directly.
 #[allow(missing_debug_implementations)]
-pub struct Column<'a, Key, Message, Theme, Renderer>
+pub struct Reorderable<'a, Key, Message, Theme, Renderer, D>
 where
     Key: Copy + PartialEq,
     Message: Clone,
     Theme: Catalog,
     Renderer: iced::advanced::Renderer,
+    D: Direction,
 {
     spacing: f32,
     padding: Padding,
@@ -79,31 +118,48 @@ where
     on_grab: Option<Box<dyn Fn(Key) -> Message + 'a>>,
     on_drag: Option<Box<dyn Fn(Key, usize) -> Message + 'a>>,
     on_drop: Option<Box<dyn Fn(Key, usize) -> Message + 'a>>,
+    on_drop_many: Option<Box<dyn Fn(Vec<Key>, usize) -> Message + 'a>>,
     on_cancel: Option<Box<dyn Fn(Key) -> Message + 'a>>,
+    on_transfer: Option<Box<dyn Fn(Key, widget::Id, widget::Id, usize) -> Message + 'a>>,
+    drag_handle: Option<Box<dyn Fn(Key, Rectangle) -> Rectangle + 'a>>,
+    can_drag: Option<Box<dyn Fn(Key) -> bool + 'a>>,
+    can_drop: Option<Box<dyn Fn(Key, usize) -> bool + 'a>>,
+    on_auto_scroll: Option<Box<dyn Fn(Vector) -> Message + 'a>>,
+    auto_scroll_edge: f32,
+    auto_scroll_speed: f32,
+    id: Option<widget::Id>,
+    group: Option<Group>,
     drop_position_marker: bool,
     drag_follow: bool,
     drag_lateral: bool,
     drag_center: bool,
+    drag_threshold: f32,
+    long_press: Option<Duration>,
+    pick_up_key: keyboard::Key,
+    animation_duration: Option<Duration>,
+    easing: Easing,
+    direction: D,
 }
 
-impl<'a, Key, Message, Theme, Renderer> Column<'a, Key, Message, Theme, Renderer>
+impl<'a, Key, Message, Theme, Renderer, D> Reorderable<'a, Key, Message, Theme, Renderer, D>
 where
     Key: Copy + PartialEq,
     Message: Clone,
     Theme: Catalog,
     Renderer: iced::advanced::Renderer,
+    D: Direction,
 {
-    /// Creates an empty [`Column`].
+    /// Creates an empty [`Reorderable`].
     pub fn new() -> Self {
         Self::from_vecs(Vec::new(), Vec::new())
     }
 
-    /// Creates a [`Column`] with the given capacity.
+    /// Creates a [`Reorderable`] with the given capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         Self::from_vecs(Vec::with_capacity(capacity), Vec::with_capacity(capacity))
     }
 
-    /// Creates a [`Column`] with the given keys and elements.
+    /// Creates a [`Reorderable`] with the given keys and elements.
     pub fn with_children(
         children: impl IntoIterator<Item = (Key, Element<'a, Message, Theme, Renderer>)>,
     ) -> Self {
@@ -112,13 +168,13 @@ where
         Self::with_capacity(iterator.size_hint().0).extend(iterator)
     }
 
-    /// Creates a [`Column`] from an already allocated [`Vec`].
+    /// Creates a [`Reorderable`] from an already allocated [`Vec`].
     ///
-    /// Keep in mind that the [`Column`] will not inspect the [`Vec`], which means
+    /// Keep in mind that the [`Reorderable`] will not inspect the [`Vec`], which means
     /// it won't automatically adapt to the sizing strategy of its contents.
     ///
     /// If any of the children have a [`Length::Fill`] strategy, you will need to
-    /// call [`Column::width`] or [`Column::height`] accordingly.
+    /// call [`Reorderable::width`] or [`Reorderable::height`] accordingly.
     pub fn from_vecs(keys: Vec<Key>, children: Vec<Element<'a, Message, Theme, Renderer>>) -> Self {
         Self {
             spacing: 0.0,
@@ -134,15 +190,31 @@ where
             on_grab: None,
             on_drag: None,
             on_drop: None,
+            on_drop_many: None,
             on_cancel: None,
+            on_transfer: None,
+            drag_handle: None,
+            can_drag: None,
+            can_drop: None,
+            on_auto_scroll: None,
+            auto_scroll_edge: 40.0,
+            auto_scroll_speed: 600.0,
+            id: None,
+            group: None,
             drop_position_marker: true,
             drag_follow: false,
             drag_lateral: false,
             drag_center: false,
+            drag_threshold: 0.0,
+            long_press: None,
+            pick_up_key: keyboard::Key::Named(keyboard::key::Named::Space),
+            animation_duration: None,
+            easing: Easing::default(),
+            direction: D::default(),
         }
     }
 
-    /// Sets the vertical spacing _between_ elements.
+    /// Sets the spacing _between_ elements along the main axis.
     ///
     /// Custom margins per element do not exist in iced. You should use this
     /// method instead! While less flexible, it helps you keep spacing between
@@ -152,47 +224,41 @@ where
         self
     }
 
-    /// Sets the [`Padding`] of the [`Column`].
+    /// Sets the [`Padding`] of the [`Reorderable`].
     pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
         self.padding = padding.into();
         self
     }
 
-    /// Sets the width of the [`Column`].
+    /// Sets the width of the [`Reorderable`].
     pub fn width(mut self, width: impl Into<Length>) -> Self {
         self.width = width.into();
         self
     }
 
-    /// Sets the height of the [`Column`].
+    /// Sets the height of the [`Reorderable`].
     pub fn height(mut self, height: impl Into<Length>) -> Self {
         self.height = height.into();
         self
     }
 
-    /// Sets the maximum width of the [`Column`].
+    /// Sets the maximum width of the [`Reorderable`].
     pub fn max_width(mut self, max_width: impl Into<Pixels>) -> Self {
         self.max_width = max_width.into().0;
         self
     }
 
-    /// Sets the horizontal alignment of the contents of the [`Column`] .
-    pub fn align_x(mut self, align: impl Into<alignment::Horizontal>) -> Self {
-        self.align = Alignment::from(align.into());
-        self
-    }
-
-    /// Sets whether the contents of the [`Column`] should be clipped on
+    /// Sets whether the contents of the [`Reorderable`] should be clipped on
     /// overflow.
     ///
     /// Note that a dragged child element will not be clipped and can be drawn
-    /// outside the bounds of the [`Column`], if dragged outside the column.
+    /// outside the bounds of the [`Reorderable`], if dragged outside the column.
     pub fn clip(mut self, clip: bool) -> Self {
         self.clip = clip;
         self
     }
 
-    /// Adds an element to the [`Column`].
+    /// Adds an element to the [`Reorderable`].
     pub fn push(
         mut self,
         key: Key,
@@ -209,7 +275,7 @@ where
         self
     }
 
-    /// Adds an element to the [`Column`], if `Some`.
+    /// Adds an element to the [`Reorderable`], if `Some`.
     pub fn push_maybe(
         self,
         key: Key,
@@ -222,7 +288,7 @@ where
         }
     }
 
-    /// Extends the [`Column`] with the given children.
+    /// Extends the [`Reorderable`] with the given children.
     pub fn extend(
         self,
         children: impl IntoIterator<Item = (Key, Element<'a, Message, Theme, Renderer>)>,
@@ -232,7 +298,7 @@ where
             .fold(self, |items, (key, child)| items.push(key, child))
     }
 
-    /// Sets the style of the [`Column`].
+    /// Sets the style of the [`Reorderable`].
     #[must_use]
     pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
     where
@@ -242,7 +308,7 @@ where
         self
     }
 
-    /// Sets the style class of the [`Column`].
+    /// Sets the style class of the [`Reorderable`].
     // #[cfg(feature = "advanced")]
     #[must_use]
     pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
@@ -250,7 +316,7 @@ where
         self
     }
 
-    /// Sets the message that will be produced when a child element on [`Column`] is grabbed
+    /// Sets the message that will be produced when a child element on [`Reorderable`] is grabbed
     /// for dragging.
     ///
     /// The message will be produced with the key of the grabbed child element.
@@ -263,10 +329,10 @@ where
     }
 
     /// Sets the message that will be produced when dragging starts after clicking a child
-    /// element or dragged child element has been dragged to another position in the [`Column`].
+    /// element or dragged child element has been dragged to another position in the [`Reorderable`].
     ///
     /// The message will be produced with the key of the dragged child element and the index
-    /// of the drag position among the [`Column`] children.
+    /// of the drag position among the [`Reorderable`] children.
     pub fn on_drag<F>(mut self, message: F) -> Self
     where
         F: Fn(Key, usize) -> Message + 'a,
@@ -276,10 +342,10 @@ where
     }
 
     /// Sets the message that will be produced when the dragged child element is dropped in
-    /// a valid drop location on the [`Column`].
+    /// a valid drop location on the [`Reorderable`].
     ///
     /// The message will be produced with the key of the dragged child element and the index
-    /// of the drop position among the [`Column`] children.
+    /// of the drop position among the [`Reorderable`] children.
     pub fn on_drop<F>(mut self, message: F) -> Self
     where
         F: Fn(Key, usize) -> Message + 'a,
@@ -288,6 +354,26 @@ where
         self
     }
 
+    /// Sets the message that will be produced when a dragged child element that is part of a
+    /// selection of several child elements is dropped in a valid drop location on the
+    /// [`Reorderable`].
+    ///
+    /// Setting this enables selecting child elements: a click selects a single child element,
+    /// a click with the command key held toggles one and a click with shift held selects the
+    /// range from the last selected child element.
+    ///
+    /// The message will be produced with the keys of the selected child elements, in their
+    /// order among the [`Reorderable`] children, and the index of the drop position. Dropping
+    /// a child element that is not part of a larger selection produces the
+    /// [`Reorderable::on_drop`] message instead.
+    pub fn on_drop_many<F>(mut self, message: F) -> Self
+    where
+        F: Fn(Vec<Key>, usize) -> Message + 'a,
+    {
+        self.on_drop_many = Some(Box::new(message));
+        self
+    }
+
     /// Sets the message that will be produced when the user cancels active dragging by
     /// right-clicking or when the dragging touch is lost.
     ///
@@ -300,7 +386,242 @@ where
         self
     }
 
-    /// Sets whether a marker line will be shown for the position among the [`Column`] children,
+    /// Sets the [`widget::Id`] of the [`Reorderable`].
+    ///
+    /// The id identifies the [`Reorderable`] among the containers of its drag [`Group`].
+    pub fn id(mut self, id: impl Into<widget::Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the drag [`Group`] of the [`Reorderable`].
+    ///
+    /// Child elements can be dragged between containers sharing the same [`Group`], as long
+    /// as each of them has an [`Reorderable::id`]. Enable [`Reorderable::drag_lateral`] if the
+    /// dragged child element should visually follow the cursor into another container.
+    pub fn drag_group(mut self, group: impl Into<Group>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Sets the message that will be produced when a child element is dragged out of this
+    /// [`Reorderable`] and dropped into another container of its drag [`Group`].
+    ///
+    /// The message will be produced with the key of the dragged child element, the id of this
+    /// [`Reorderable`], the id of the container it was dropped into and the index of the drop
+    /// position among that container's children.
+    ///
+    /// A dragged selection of several child elements produces one message per child element,
+    /// in their order among the [`Reorderable`] children and with consecutive drop positions,
+    /// so applying them in order keeps the selection together.
+    pub fn on_transfer<F>(mut self, message: F) -> Self
+    where
+        F: Fn(Key, widget::Id, widget::Id, usize) -> Message + 'a,
+    {
+        self.on_transfer = Some(Box::new(message));
+        self
+    }
+
+    /// Sets the region of each child element that has to be pressed to grab it for dragging.
+    ///
+    /// The closure receives the key and the bounds of a child element and returns the bounds
+    /// of its drag handle. Without a drag handle, a child element can be grabbed anywhere, so
+    /// interactive widgets inside it, like text inputs, will compete with dragging.
+    pub fn drag_handle<F>(mut self, handle: F) -> Self
+    where
+        F: Fn(Key, Rectangle) -> Rectangle + 'a,
+    {
+        self.drag_handle = Some(Box::new(handle));
+        self
+    }
+
+    /// Sets the predicate deciding whether the child element with the given key can be
+    /// dragged. Child elements for which it returns `false` stay in place, e.g. pinned ones.
+    pub fn can_drag<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(Key) -> bool + 'a,
+    {
+        self.can_drag = Some(Box::new(predicate));
+        self
+    }
+
+    /// Sets the predicate deciding whether the child element with the given key can be
+    /// dropped at the given index among the [`Reorderable`] children, e.g. to keep a pinned
+    /// child element at index `0`.
+    ///
+    /// Rejected drop locations are shown with the [`Style::rejected`] marker color, and
+    /// dropping there cancels the drag. A dragged selection is only accepted where every one
+    /// of its child elements is.
+    pub fn can_drop<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(Key, usize) -> bool + 'a,
+    {
+        self.can_drop = Some(Box::new(predicate));
+        self
+    }
+
+    /// Returns whether the child element with the given key can be dragged.
+    fn is_draggable(&self, key: Key) -> bool {
+        self.can_drag.as_deref().is_none_or(|can_drag| can_drag(key))
+    }
+
+    /// Returns whether the child element with the given key can be dropped at `drop_location`.
+    fn accepts(&self, key: Key, drop_location: usize) -> bool {
+        self.can_drop
+            .as_deref()
+            .is_none_or(|can_drop| can_drop(key, drop_location))
+    }
+
+    /// Returns whether the child element with the given key, and the rest of the selection if
+    /// it is part of it, can be dropped at `drop_location`.
+    fn accepts_dragged(&self, selection: &[Key], key: Key, drop_location: usize) -> bool {
+        self.dragged_keys(selection, key)
+            .into_iter()
+            .all(|key| self.accepts(key, drop_location))
+    }
+
+    /// Returns the region of a child element with the given key and bounds that grabs it, if
+    /// it can be dragged.
+    fn grab_area(&self, key: Key, bounds: Rectangle) -> Option<Rectangle> {
+        if !self.is_draggable(key) {
+            return None;
+        }
+        Some(
+            self.drag_handle
+                .as_deref()
+                .map_or(bounds, |drag_handle| drag_handle(key, bounds)),
+        )
+    }
+
+    /// Sets the message that will be produced to scroll the content while a child element is
+    /// dragged near an edge of the visible part of the [`Reorderable`].
+    ///
+    /// The message will be produced with the offset to scroll by, e.g. with
+    /// `scrollable::scroll_by`, on every frame the cursor stays near an edge.
+    pub fn on_auto_scroll<F>(mut self, message: F) -> Self
+    where
+        F: Fn(Vector) -> Message + 'a,
+    {
+        self.on_auto_scroll = Some(Box::new(message));
+        self
+    }
+
+    /// Sets the size of the edges of the visible part of the [`Reorderable`] where dragging
+    /// a child element scrolls the content. Defaults to 40 pixels.
+    pub fn auto_scroll_edge(mut self, edge: impl Into<Pixels>) -> Self {
+        self.auto_scroll_edge = edge.into().0;
+        self
+    }
+
+    /// Sets the maximum auto-scroll speed, in pixels per second, reached when the dragged child
+    /// element is at the very edge. Defaults to 600 pixels per second.
+    pub fn auto_scroll_speed(mut self, speed: f32) -> Self {
+        self.auto_scroll_speed = speed;
+        self
+    }
+
+    /// Sets the key that picks up the focused child element for reordering it with the
+    /// keyboard. Defaults to the space bar.
+    ///
+    /// While a child element is picked up, the arrow keys move its drop location, `Enter`
+    /// drops it and `Escape` cancels.
+    pub fn pick_up_key(mut self, key: keyboard::Key) -> Self {
+        self.pick_up_key = key;
+        self
+    }
+
+    /// Sets whether the other child elements should animate out of the way of a dragged child
+    /// element, opening a gap at its drop location, and how long each animation takes.
+    ///
+    /// A dropped child element also animates from where it was released into its final slot.
+    /// This relies on the keys of the child elements staying the same after reordering, so
+    /// they should not be positions in the underlying collection.
+    ///
+    /// Replaces the drop position marker while enabled.
+    pub fn animate(mut self, duration: Option<Duration>) -> Self {
+        self.animation_duration = duration;
+        self
+    }
+
+    /// Sets the [`Easing`] of the animations enabled with [`Reorderable::animate`].
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Updates the selection for a click on the child element with the given key.
+    fn select(&self, state: &mut State<Key>, key: Key, modifiers: keyboard::Modifiers) {
+        let position = |key: Key| self.keys.iter().position(|other| *other == key);
+        let range = state.anchor.and_then(position).zip(position(key));
+
+        match range {
+            Some((anchor, index)) if modifiers.shift() => {
+                state.selection = self.keys[anchor.min(index)..=anchor.max(index)].to_vec();
+            }
+            _ if modifiers.command() => {
+                if state.selection.contains(&key) {
+                    state.selection.retain(|other| *other != key);
+                } else {
+                    state.selection.push(key);
+                }
+                state.anchor = Some(key);
+            }
+            _ => {
+                state.selection = vec![key];
+                state.anchor = Some(key);
+            }
+        }
+    }
+
+    /// Returns the keys of the child elements dragged along with the one with the given key:
+    /// the draggable part of the selection if the key is part of it, or only the key itself.
+    ///
+    /// The keys are in their order among the [`Reorderable`] children.
+    fn dragged_keys(&self, selection: &[Key], key: Key) -> Vec<Key> {
+        if self.on_drop_many.is_some() && selection.len() > 1 && selection.contains(&key) {
+            self.keys
+                .iter()
+                .copied()
+                .filter(|other| selection.contains(other) && self.is_draggable(*other))
+                .collect()
+        } else {
+            vec![key]
+        }
+    }
+
+    /// Returns the message for dropping the child element with the given key at
+    /// `drop_location`, along with the rest of the selection if it is part of it.
+    fn drop_message(&self, selection: &[Key], key: Key, drop_location: usize) -> Option<Message> {
+        let keys = self.dragged_keys(selection, key);
+        if keys.len() > 1 {
+            if let Some(on_drop_many) = self.on_drop_many.as_deref() {
+                return Some((on_drop_many)(keys, drop_location));
+            }
+        }
+        self.on_drop
+            .as_deref()
+            .map(|on_drop| (on_drop)(key, drop_location))
+    }
+
+    /// Returns the order of the child elements after dropping the `dragged` ones at
+    /// `drop_location`, as their current indices.
+    fn reordered(&self, dragged: &[Key], drop_location: usize) -> Vec<usize> {
+        let indices: Vec<usize> = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| dragged.contains(key))
+            .map(|(index, _)| index)
+            .collect();
+        animation::reordered(self.keys.len(), &indices, drop_location)
+    }
+
+    /// Returns the drag [`Group`] and id of the [`Reorderable`], if it takes part in one.
+    fn group_member(&self) -> Option<(&Group, &widget::Id)> {
+        self.group.as_ref().zip(self.id.as_ref())
+    }
+
+    /// Sets whether a marker line will be shown for the position among the [`Reorderable`] children,
     /// where the dragged child element would be dropped if mouse button press or touch was
     /// released at current position.
     pub fn drop_position_marker(mut self, drop_position_marker: bool) -> Self {
@@ -315,12 +636,13 @@ where
     }
 
     /// Sets whether a child element should follow the cursor laterally on the cross axis of the
-    /// [`Column`] while being dragged.
+    /// [`Reorderable`] while being dragged.
     ///
-    /// If set to `false`, child elements will only follow the cursor vertically along the main
-    /// axis of the [`Column`] while being dragged.
+    /// If set to `false`, child elements will only follow the cursor along the main axis of the
+    /// [`Reorderable`] while being dragged. A [`Grid`] has no single main axis, so its children
+    /// always follow the cursor in both directions.
     ///
-    /// This has no effect if [`Column::drag_follow`] is set to `false`.
+    /// This has no effect if [`Reorderable::drag_follow`] is set to `false`.
     pub fn drag_lateral(mut self, drag_lateral: bool) -> Self {
         self.drag_lateral = drag_lateral;
         self
@@ -328,32 +650,109 @@ where
 
     /// Sets whether a child element should be centered on the cursor while being dragged.
     ///
-    /// This has no effect if [`Column::drag_follow`] is set to `false`.
+    /// This has no effect if [`Reorderable::drag_follow`] is set to `false`.
     pub fn drag_center(mut self, drag_center: bool) -> Self {
         self.drag_center = drag_center;
         self
     }
+
+    /// Sets the distance in pixels the cursor has to move away from where a child element was
+    /// grabbed before it starts being dragged. Defaults to `0.0`.
+    ///
+    /// Releasing a grabbed child element before it moved that far is a click, which cancels.
+    pub fn drag_threshold(mut self, drag_threshold: impl Into<Pixels>) -> Self {
+        self.drag_threshold = drag_threshold.into().0;
+        self
+    }
+
+    /// Sets how long a child element has to be held before it starts being dragged.
+    ///
+    /// Moving the cursor or finger further than [`Reorderable::drag_threshold`] before the
+    /// delay elapsed abandons the grab, so touch gestures like scrolling can pass over the
+    /// children of the [`Reorderable`].
+    pub fn long_press(mut self, delay: Option<Duration>) -> Self {
+        self.long_press = delay;
+        self
+    }
+
+    /// Returns whether a grab at `time` has been held long enough at `now` to start dragging.
+    fn long_pressed(&self, time: Instant, now: Instant) -> bool {
+        self.long_press
+            .is_none_or(|delay| now.saturating_duration_since(time) >= delay)
+    }
+}
+
+impl<'a, Key, Message, Theme, Renderer> Column<'a, Key, Message, Theme, Renderer>
+where
+    Key: Copy + PartialEq,
+    Message: Clone,
+    Theme: Catalog,
+    Renderer: iced::advanced::Renderer,
+{
+    /// Sets the horizontal alignment of the contents of the [`Column`] .
+    pub fn align_x(mut self, align: impl Into<alignment::Horizontal>) -> Self {
+        self.align = Alignment::from(align.into());
+        self
+    }
 }
 
-impl<'a, Key, Message, Theme, Renderer> Default for Column<'a, Key, Message, Theme, Renderer>
+impl<'a, Key, Message, Theme, Renderer> Row<'a, Key, Message, Theme, Renderer>
 where
     Key: Copy + PartialEq,
     Message: Clone,
     Theme: Catalog,
     Renderer: iced::advanced::Renderer,
+{
+    /// Sets the vertical alignment of the contents of the [`Row`] .
+    pub fn align_y(mut self, align: impl Into<alignment::Vertical>) -> Self {
+        self.align = Alignment::from(align.into());
+        self
+    }
+}
+
+impl<'a, Key, Message, Theme, Renderer> Grid<'a, Key, Message, Theme, Renderer>
+where
+    Key: Copy + PartialEq,
+    Message: Clone,
+    Theme: Catalog,
+    Renderer: iced::advanced::Renderer,
+{
+    /// Sets the vertical alignment of the contents of each line of the [`Grid`] .
+    pub fn align_y(mut self, align: impl Into<alignment::Vertical>) -> Self {
+        self.align = Alignment::from(align.into());
+        self
+    }
+
+    /// Sets the vertical spacing _between_ lines of the [`Grid`].
+    ///
+    /// Defaults to the horizontal spacing set with [`Reorderable::spacing`].
+    pub fn line_spacing(mut self, amount: impl Into<Pixels>) -> Self {
+        self.direction.line_spacing = Some(amount.into().0);
+        self
+    }
+}
+
+impl<'a, Key, Message, Theme, Renderer, D> Default for Reorderable<'a, Key, Message, Theme, Renderer, D>
+where
+    Key: Copy + PartialEq,
+    Message: Clone,
+    Theme: Catalog,
+    Renderer: iced::advanced::Renderer,
+    D: Direction,
 {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'a, Key, Message, Theme, Renderer> FromIterator<(Key, Element<'a, Message, Theme, Renderer>)>
-    for Column<'a, Key, Message, Theme, Renderer>
+impl<'a, Key, Message, Theme, Renderer, D> FromIterator<(Key, Element<'a, Message, Theme, Renderer>)>
+    for Reorderable<'a, Key, Message, Theme, Renderer, D>
 where
     Key: Copy + PartialEq,
     Message: Clone,
     Theme: Catalog,
     Renderer: iced::advanced::Renderer,
+    D: Direction,
 {
     fn from_iter<T: IntoIterator<Item = (Key, Element<'a, Message, Theme, Renderer>)>>(
         iter: T,
@@ -362,13 +761,148 @@ where
     }
 }
 
-impl<'a, Key, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
-    for Column<'a, Key, Message, Theme, Renderer>
+impl<'a, Key, Message, Theme, Renderer, D> Reorderable<'a, Key, Message, Theme, Renderer, D>
+where
+    Key: Copy + PartialEq + 'static,
+    Message: Clone,
+    Theme: Catalog,
+    Renderer: iced::advanced::Renderer,
+    D: Direction,
+{
+    /// Moves the child element with the given key, grabbed at `origin`, to `position`.
+    fn drag_to(
+        &self,
+        state: &mut State<Key>,
+        key: Key,
+        origin: Point,
+        mut position: Point,
+        layout: &Layout<'_>,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        if !self.drag_lateral {
+            position = self.direction.lock_to_axis(position, origin);
+        }
+        let drop_location = self.direction.drop_location(layout, position);
+        if let Some(on_drag) = self.on_drag.as_deref() {
+            if Some(drop_location) != state.drag.drop_location() {
+                let message = (on_drag)(key, drop_location);
+                shell.publish(message);
+            }
+        }
+        if let (DragState::Grabbed { .. }, Some((group, id))) = (state.drag, self.group_member()) {
+            group::start(group, self.dragged_keys(&state.selection, key), id.clone());
+        }
+        let moved = Some(drop_location) != state.drag.drop_location();
+        state.drag = DragState::Dragged {
+            key,
+            origin,
+            position,
+            drop_location,
+        };
+        if self.drag_follow || (moved && self.animation_duration.is_some()) {
+            shell.request_redraw();
+        }
+    }
+
+    /// Advances the animated offsets of the child elements by one frame.
+    fn animate(
+        &self,
+        state: &mut State<Key>,
+        layout: &Layout<'_>,
+        duration: Duration,
+        now: Instant,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let dragged = state
+            .drag
+            .key()
+            .map(|key| self.dragged_keys(&state.selection, key))
+            .unwrap_or_default();
+        let preview = match state.drag {
+            DragState::Dragged { drop_location, .. } | DragState::Picked { drop_location, .. } => {
+                Some(self.reordered(&dragged, drop_location))
+            }
+            DragState::Idle | DragState::Grabbed { .. } => None,
+        };
+        let offsets = match preview {
+            Some(order) => self.direction.preview(layout, self.spacing, &order),
+            None => vec![Vector::ZERO; self.keys.len()],
+        };
+        let following = match state.drag {
+            DragState::Dragged {
+                origin, position, ..
+            } if self.drag_follow => Some(position - origin),
+            _ => None,
+        };
+
+        let targets = self
+            .keys
+            .iter()
+            .zip(layout.children())
+            .zip(offsets)
+            .map(|((key, item_layout), offset)| {
+                let position = item_layout.bounds().position();
+                match following {
+                    Some(drag_offset) if dragged.contains(key) => animation::Target {
+                        key: *key,
+                        position,
+                        offset: drag_offset,
+                        immediate: true,
+                    },
+                    _ => animation::Target {
+                        key: *key,
+                        position,
+                        offset,
+                        immediate: false,
+                    },
+                }
+            });
+        let animation = animation::Animation {
+            duration,
+            easing: self.easing,
+        };
+
+        if animation::step(&mut state.offsets, targets, animation, now) {
+            shell.request_redraw();
+        }
+    }
+
+    /// Returns the auto-scroll velocity, in pixels per second, for a cursor at `position`.
+    ///
+    /// The velocity grows from zero at the inner border of an edge of the `viewport` up to the
+    /// full auto-scroll speed at the edge itself.
+    fn auto_scroll_velocity(&self, viewport: &Rectangle, position: Point) -> Vector {
+        let edge = self.auto_scroll_edge;
+        if edge <= 0.0 {
+            return Vector::ZERO;
+        }
+        let axis = |start: f32, length: f32, position: f32| {
+            let from_start = position - start;
+            let from_end = start + length - position;
+            if from_start < edge {
+                -(1.0 - from_start.max(0.0) / edge)
+            } else if from_end < edge {
+                1.0 - from_end.max(0.0) / edge
+            } else {
+                0.0
+            }
+        };
+
+        Vector::new(
+            axis(viewport.x, viewport.width, position.x),
+            axis(viewport.y, viewport.height, position.y),
+        ) * self.auto_scroll_speed
+    }
+}
+
+impl<'a, Key, Message, Theme, Renderer, D> Widget<Message, Theme, Renderer>
+    for Reorderable<'a, Key, Message, Theme, Renderer, D>
 where
     Key: Copy + PartialEq + 'static,
     Message: Clone,
     Theme: Catalog,
     Renderer: iced::advanced::Renderer,
+    D: Direction,
 {
     fn state(&self) -> iced::advanced::widget::tree::State {
         iced::advanced::widget::tree::State::new(State::<Key>::default())
@@ -426,22 +960,68 @@ where
         }
 
         let state = tree.state.downcast_mut::<State<Key>>();
+        state.selection.retain(|key| self.keys.contains(key));
+
+        if let DragState::Picked { key, .. } = state.drag {
+            // A child element picked up with the keyboard is put back when the
+            // [`Reorderable`] loses focus or a pointer drag begins.
+            let pointer_pressed = matches!(
+                event,
+                Event::Mouse(mouse::Event::ButtonPressed(_))
+                    | Event::Touch(touch::Event::FingerPressed { .. })
+            );
+            if state.focus.is_none() || pointer_pressed {
+                state.drag = DragState::Idle;
+                if let Some(on_cancel) = &self.on_cancel {
+                    shell.publish(on_cancel(key));
+                }
+                shell.request_redraw();
+            }
+        }
+
         match event {
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
             | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                if !cursor.is_over(layout.bounds()) && state.focus.take().is_some() {
+                    shell.request_redraw();
+                }
                 if !shell.is_event_captured() && cursor.is_over(layout.bounds()) {
                     let mut position = cursor.position().unwrap();
-                    for (key, item_layout) in self.keys.iter().zip(layout.children()) {
+                    for (index, (key, item_layout)) in
+                        self.keys.iter().zip(layout.children()).enumerate()
+                    {
                         if cursor.is_over(item_layout.bounds()) {
+                            if state.focus.is_some() {
+                                state.focus = Some(index);
+                            }
+                            if self.on_drop_many.is_some() {
+                                let modifiers = state.modifiers;
+                                if modifiers.command() || modifiers.shift() {
+                                    self.select(state, *key, modifiers);
+                                    shell.capture_event();
+                                    shell.request_redraw();
+                                    break;
+                                } else if !state.selection.contains(key) {
+                                    self.select(state, *key, modifiers);
+                                }
+                            }
+                            if !self
+                                .grab_area(*key, item_layout.bounds())
+                                .is_some_and(|grab_area| cursor.is_over(grab_area))
+                            {
+                                shell.request_redraw();
+                                break;
+                            }
                             if let Some(on_grab) = &self.on_grab {
                                 shell.publish(on_grab(*key));
                             };
-                            if self.drag_center {
+                            let immediate = self.long_press.is_none() && self.drag_threshold <= 0.0;
+                            if self.drag_center && immediate {
                                 let origin = item_layout.bounds().center();
                                 if !self.drag_lateral {
-                                    position.x = origin.x;
+                                    position = self.direction.lock_to_axis(position, origin);
                                 }
-                                let drop_location = drop_location(&layout, position);
+                                let drop_location = self.direction.drop_location(&layout, position);
                                 if let Some(on_drag) = self.on_drag.as_deref() {
                                     if Some(drop_location) != state.drag.drop_location() {
                                         let message = (on_drag)(*key, drop_location);
@@ -454,9 +1034,26 @@ where
                                     position,
                                     drop_location,
                                 };
+                                if let Some((group, id)) = self.group_member() {
+                                    let keys = self.dragged_keys(&state.selection, *key);
+                                    group::start(group, keys, id.clone());
+                                }
                             } else {
-                                let origin = position;
-                                state.drag = DragState::Grabbed { key: *key, origin };
+                                let origin = if self.drag_center {
+                                    item_layout.bounds().center()
+                                } else {
+                                    position
+                                };
+                                let time = Instant::now();
+                                state.drag = DragState::Grabbed {
+                                    key: *key,
+                                    origin,
+                                    press: position,
+                                    time,
+                                };
+                                if let Some(delay) = self.long_press {
+                                    shell.request_redraw_at(time + delay);
+                                }
                             };
                             shell.request_redraw();
                             break;
@@ -467,20 +1064,32 @@ where
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right))
             | Event::Touch(touch::Event::FingerLost { .. }) => {
                 if let Some(key) = state.drag.key() {
+                    if let (DragState::Dragged { .. }, Some((group, _))) =
+                        (state.drag, self.group_member())
+                    {
+                        group::finish(group);
+                    }
                     state.drag = DragState::Idle;
                     if let Some(on_cancel) = &self.on_cancel {
                         shell.publish(on_cancel(key));
                     }
                     shell.request_redraw();
+                } else if state.transfer_hover.take().is_some() {
+                    shell.request_redraw();
                 }
             }
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
             | Event::Touch(touch::Event::FingerLifted { .. }) => match state.drag {
-                DragState::Grabbed { key, origin: _ } => {
+                DragState::Grabbed { key, .. } => {
                     if let Some(on_cancel) = &self.on_cancel {
                         shell.publish(on_cancel(key));
                     }
                     state.drag = DragState::Idle;
+                    // A click without dragging narrows a selection down to the clicked child element.
+                    if self.on_drop_many.is_some() && state.selection.len() > 1 {
+                        self.select(state, key, keyboard::Modifiers::default());
+                        shell.request_redraw();
+                    }
                 }
                 DragState::Dragged {
                     key,
@@ -488,48 +1097,256 @@ where
                     position,
                     drop_location: _,
                 } => {
-                    if let Some(on_drop) = self.on_drop.as_deref() {
-                        let drop_index = drop_location(&layout, position);
-                        let message = (on_drop)(key, drop_index);
-                        shell.publish(message);
+                    let target = self.group_member().and_then(|(group, id)| {
+                        let transfer = group::transfer::<Key>(group);
+                        group::finish(group);
+                        transfer
+                            .and_then(|transfer| transfer.target)
+                            .filter(|_| !cursor.is_over(layout.bounds()))
+                            .map(|(target, drop_index)| (id.clone(), target, drop_index))
+                    });
+                    if let Some((source, target, drop_index)) = target {
+                        if let Some(on_transfer) = self.on_transfer.as_deref() {
+                            let keys = self.dragged_keys(&state.selection, key);
+                            for (offset, key) in keys.into_iter().enumerate() {
+                                let message = (on_transfer)(
+                                    key,
+                                    source.clone(),
+                                    target.clone(),
+                                    drop_index + offset,
+                                );
+                                shell.publish(message);
+                            }
+                        } else if let Some(on_cancel) = &self.on_cancel {
+                            shell.publish(on_cancel(key));
+                        }
+                    } else {
+                        let drop_index = self.direction.drop_location(&layout, position);
+                        if !self.accepts_dragged(&state.selection, key, drop_index) {
+                            if let Some(on_cancel) = &self.on_cancel {
+                                shell.publish(on_cancel(key));
+                            }
+                        } else if let Some(message) =
+                            self.drop_message(&state.selection, key, drop_index)
+                        {
+                            shell.publish(message);
+                        }
                     }
                     state.drag = DragState::Idle;
+                    if self.animation_duration.is_some() {
+                        shell.request_redraw();
+                    }
+                }
+                DragState::Idle => {
+                    if state.transfer_hover.take().is_some() {
+                        shell.request_redraw();
+                    }
                 }
-                _ => (),
+                DragState::Picked { .. } => {}
             },
             Event::Mouse(mouse::Event::CursorMoved { .. })
             | Event::Touch(touch::Event::FingerMoved { .. }) => match state.drag {
-                DragState::Grabbed { key, origin } | DragState::Dragged { key, origin, .. } => {
-                    if cursor.position() == state.drag.last_position() {
+                DragState::Grabbed {
+                    key,
+                    origin,
+                    press,
+                    time,
+                } => {
+                    let Some(position) = cursor.position() else {
                         return;
-                    } else if let Some(mut position) = cursor.position() {
-                        if !self.drag_lateral {
-                            position.x = origin.x;
+                    };
+                    let moved = position.distance(press) > self.drag_threshold;
+                    if self.long_pressed(time, Instant::now()) {
+                        if moved || self.long_press.is_some() {
+                            self.drag_to(state, key, origin, position, &layout, shell);
                         }
-                        let drop_location = drop_location(&layout, position);
-                        if let Some(on_drag) = self.on_drag.as_deref() {
-                            if Some(drop_location) != state.drag.drop_location() {
-                                let message = (on_drag)(key, drop_location);
-                                shell.publish(message);
-                            }
+                    } else if moved {
+                        // Moving away before the long press elapsed is some other gesture.
+                        state.drag = DragState::Idle;
+                        if let Some(on_cancel) = &self.on_cancel {
+                            shell.publish(on_cancel(key));
                         }
-                        state.drag = DragState::Dragged {
-                            key,
-                            origin,
-                            position,
-                            drop_location,
-                        };
-                        if self.drag_follow {
+                        shell.request_redraw();
+                    }
+                }
+                DragState::Dragged { key, origin, .. } => {
+                    if cursor.position() == state.drag.last_position() {
+                        return;
+                    } else if let Some(position) = cursor.position() {
+                        self.drag_to(state, key, origin, position, &layout, shell);
+                    }
+                }
+                DragState::Idle => {
+                    if let Some((group, id)) = self.group_member() {
+                        let transfer = group::transfer::<Key>(group)
+                            .filter(|transfer| transfer.source != *id);
+                        let hover = transfer
+                            .zip(cursor.position_over(layout.bounds()))
+                            .map(|(transfer, position)| {
+                                let drop_location = self.direction.drop_location(&layout, position);
+                                let accepted = transfer
+                                    .keys
+                                    .iter()
+                                    .all(|key| self.accepts(*key, drop_location));
+                                (drop_location, accepted)
+                            });
+                        if hover.map(|(drop_location, _)| drop_location) != state.transfer_hover {
+                            let target = hover
+                                .filter(|(_, accepted)| *accepted)
+                                .map(|(drop_location, _)| drop_location);
+                            group::set_target::<Key>(group, id, target);
+                            state.transfer_hover = hover.map(|(drop_location, _)| drop_location);
                             shell.request_redraw();
                         }
                     }
-                }
-                _ => {
                     if cursor.is_over(layout.bounds()) {
                         shell.request_redraw();
                     }
                 }
+                DragState::Picked { .. } => {}
             },
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                if let Some(duration) = self.animation_duration {
+                    self.animate(state, &layout, duration, *now, shell);
+                }
+                if let DragState::Grabbed {
+                    key,
+                    origin,
+                    press,
+                    time,
+                } = state.drag
+                {
+                    if self.long_press.is_some() && self.long_pressed(time, *now) {
+                        let position = cursor.position().unwrap_or(press);
+                        self.drag_to(state, key, origin, position, &layout, shell);
+                        shell.request_redraw();
+                    }
+                }
+                let DragState::Dragged { key, origin, .. } = state.drag else {
+                    state.auto_scroll = None;
+                    return;
+                };
+                let Some(position) = cursor.position() else {
+                    state.auto_scroll = None;
+                    return;
+                };
+                if let Some(on_auto_scroll) = self.on_auto_scroll.as_deref() {
+                    let velocity = self.auto_scroll_velocity(viewport, position);
+                    if velocity == Vector::ZERO {
+                        state.auto_scroll = None;
+                    } else {
+                        if let Some(last_scroll) = state.auto_scroll {
+                            let elapsed = now.saturating_duration_since(last_scroll).as_secs_f32();
+                            shell.publish(on_auto_scroll(velocity * elapsed));
+                        }
+                        state.auto_scroll = Some(*now);
+                        shell.request_redraw();
+                    }
+                }
+                // Scrolling moves the content under a resting cursor, which only shows up
+                // as a changed cursor position relative to the [`Reorderable`].
+                let locked_position = if self.drag_lateral {
+                    position
+                } else {
+                    self.direction.lock_to_axis(position, origin)
+                };
+                if Some(locked_position) != state.drag.last_position() {
+                    self.drag_to(state, key, origin, position, &layout, shell);
+                }
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key: pressed, .. }) => {
+                let count = self.keys.len();
+                let Some(focus) = state.focus.filter(|_| count > 0) else {
+                    return;
+                };
+                if shell.is_event_captured() {
+                    return;
+                }
+                let focus = focus.min(count - 1);
+                match state.drag {
+                    DragState::Idle => {
+                        if *pressed == self.pick_up_key && self.is_draggable(self.keys[focus]) {
+                            let key = self.keys[focus];
+                            if let Some(on_grab) = &self.on_grab {
+                                shell.publish(on_grab(key));
+                            }
+                            if let Some(on_drag) = self.on_drag.as_deref() {
+                                shell.publish((on_drag)(key, focus));
+                            }
+                            state.drag = DragState::Picked {
+                                key,
+                                index: focus,
+                                drop_location: focus,
+                            };
+                        } else if let Some(step) = keyboard_step(pressed) {
+                            state.focus = Some(focus.saturating_add_signed(step).min(count - 1));
+                        } else {
+                            return;
+                        }
+                    }
+                    DragState::Picked {
+                        key,
+                        index,
+                        drop_location,
+                    } => match pressed {
+                        // Like releasing the mouse there, dropping at a rejected location
+                        // cancels the drag.
+                        keyboard::Key::Named(keyboard::key::Named::Enter)
+                            if !self.accepts_dragged(&state.selection, key, drop_location) =>
+                        {
+                            if let Some(on_cancel) = &self.on_cancel {
+                                shell.publish(on_cancel(key));
+                            }
+                            state.drag = DragState::Idle;
+                        }
+                        keyboard::Key::Named(keyboard::key::Named::Enter) => {
+                            if let Some(message) =
+                                self.drop_message(&state.selection, key, drop_location)
+                            {
+                                shell.publish(message);
+                            }
+                            // The focus follows the picked up child element, wherever the rest
+                            // of the selection moved it.
+                            let dragged = self.dragged_keys(&state.selection, key);
+                            let new_index = self
+                                .reordered(&dragged, drop_location)
+                                .iter()
+                                .position(|other| *other == index)
+                                .unwrap_or(index);
+                            state.focus = Some(new_index.min(count - 1));
+                            state.drag = DragState::Idle;
+                        }
+                        keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                            if let Some(on_cancel) = &self.on_cancel {
+                                shell.publish(on_cancel(key));
+                            }
+                            state.drag = DragState::Idle;
+                        }
+                        _ => {
+                            let Some(step) = keyboard_step(pressed) else {
+                                return;
+                            };
+                            let new_location = step_drop_location(index, drop_location, step, count);
+                            if new_location != drop_location {
+                                if let Some(on_drag) = self.on_drag.as_deref() {
+                                    shell.publish((on_drag)(key, new_location));
+                                }
+                                state.drag = DragState::Picked {
+                                    key,
+                                    index,
+                                    drop_location: new_location,
+                                };
+                            }
+                        }
+                    },
+                    DragState::Grabbed { .. } | DragState::Dragged { .. } => return,
+                }
+                shell.capture_event();
+                shell.request_redraw();
+            }
             _ => {}
         }
     }
@@ -542,8 +1359,7 @@ where
     ) -> layout::Node {
         let limits = limits.max_width(self.max_width);
 
-        layout::flex::resolve(
-            layout::flex::Axis::Vertical,
+        self.direction.layout(
             renderer,
             &limits,
             self.width,
@@ -563,7 +1379,10 @@ where
         renderer: &Renderer,
         operation: &mut dyn Operation,
     ) {
-        operation.container(None, layout.bounds(), &mut |operation| {
+        let state = tree.state.downcast_mut::<State<Key>>();
+        operation.focusable(state, self.id.as_ref());
+
+        operation.container(self.id.as_ref(), layout.bounds(), &mut |operation| {
             self.children
                 .iter()
                 .zip(&mut tree.children)
@@ -585,15 +1404,16 @@ where
         renderer: &Renderer,
     ) -> mouse::Interaction {
         let drag_state = tree.state.downcast_ref::<State<Key>>().drag;
-        if !drag_state.is_idle() {
+        if drag_state.is_pointer_drag() {
             return mouse::Interaction::Grabbing;
         }
 
         self.children
             .iter()
+            .zip(&self.keys)
             .zip(&tree.children)
             .zip(layout.children())
-            .map(|((child, state), item_layout)| {
+            .map(|(((child, key), state), item_layout)| {
                 let child_interaction = child.as_widget().mouse_interaction(
                     state,
                     item_layout,
@@ -601,7 +1421,10 @@ where
                     viewport,
                     renderer,
                 );
-                if self.on_drop.is_some() && cursor.is_over(item_layout.bounds()) {
+                let grabbable = self
+                    .grab_area(*key, item_layout.bounds())
+                    .is_some_and(|grab_area| cursor.is_over(grab_area));
+                if self.on_drop.is_some() && grabbable {
                     mouse::Interaction::Pointer.max(child_interaction)
                 } else {
                     child_interaction
@@ -629,28 +1452,68 @@ where
             };
             let state = tree.state.downcast_ref::<State<Key>>();
 
-            let mut deferred_drop_marker_y = None;
-            let mut deferred_dragged_elem_key = None;
-            let mut deferred_dragged_elem_translation = Vector::ZERO;
+            let mut deferred_drop_marker = None;
+            let mut drop_accepted = true;
+            let mut dragged_elem_keys = Vec::new();
 
-            if let DragState::Dragged {
-                key,
-                origin,
-                position,
-                drop_location,
-            } = state.drag
-            {
-                if self.drop_position_marker {
-                    deferred_drop_marker_y =
-                        drop_location_marker_y(&layout, self.spacing, drop_location);
+            match state.drag {
+                DragState::Dragged {
+                    key, drop_location, ..
+                } => {
+                    let transferred = self.group_member().is_some_and(|(group, _)| {
+                        group::transfer::<Key>(group).is_some_and(|transfer| transfer.target.is_some())
+                    });
+                    if self.drop_position_marker && self.animation_duration.is_none() && !transferred {
+                        deferred_drop_marker = self.direction.drop_marker(
+                            &layout,
+                            self.padding,
+                            self.spacing,
+                            drop_location,
+                        );
+                        drop_accepted = self.accepts_dragged(&state.selection, key, drop_location);
+                    }
+                    if self.drag_follow {
+                        dragged_elem_keys = self.dragged_keys(&state.selection, key);
+                    }
                 }
-                if self.drag_follow {
-                    deferred_dragged_elem_key = Some(key);
-                    deferred_dragged_elem_translation = position - origin;
+                DragState::Idle => {
+                    if let Some(drop_location) = state.transfer_hover.filter(|_| self.drop_position_marker) {
+                        deferred_drop_marker = self
+                            .direction
+                            .drop_marker(&layout, self.padding, self.spacing, drop_location)
+                            .or_else(|| empty_drop_marker(&layout, self.padding));
+                        drop_accepted = self
+                            .group
+                            .as_ref()
+                            .and_then(group::transfer::<Key>)
+                            .is_none_or(|transfer| {
+                                transfer.keys.iter().all(|key| self.accepts(*key, drop_location))
+                            });
+                    }
                 }
+                DragState::Picked {
+                    key, drop_location, ..
+                } => {
+                    if self.drop_position_marker && self.animation_duration.is_none() {
+                        deferred_drop_marker = self.direction.drop_marker(
+                            &layout,
+                            self.padding,
+                            self.spacing,
+                            drop_location,
+                        );
+                        drop_accepted = self.accepts_dragged(&state.selection, key, drop_location);
+                    }
+                }
+                DragState::Grabbed { .. } => {}
             }
+            let focused_bounds = state
+                .focus
+                .and_then(|focus| layout.children().nth(focus))
+                .map(|item_layout| item_layout.bounds());
 
-            let mut deferred_dragged_elem = None;
+            let offsets = &state.offsets;
+            let selection = &state.selection;
+            let selection_color = theme.style(&self.class).selection;
 
             for (((child, key), state), item_layout) in self
                 .children
@@ -658,75 +1521,80 @@ where
                 .zip(&self.keys)
                 .zip(&tree.children)
                 .zip(layout.children())
-                .filter(|(_, item_layout)| item_layout.bounds().intersects(viewport))
             {
-                if Some(*key) == deferred_dragged_elem_key {
-                    deferred_dragged_elem = Some((child, state, item_layout));
+                // Dragged child elements following the cursor are drawn by their overlay.
+                if dragged_elem_keys.contains(key) {
                     continue;
                 }
 
-                child.as_widget().draw(
-                    state,
-                    renderer,
-                    theme,
-                    style,
-                    item_layout,
-                    cursor,
-                    viewport,
-                );
-            }
+                let offset = animation::current(offsets, *key);
+                if !(item_layout.bounds() + offset).intersects(viewport) {
+                    continue;
+                }
 
-            if deferred_drop_marker_y.is_some() || deferred_dragged_elem.is_some() {
-                renderer.with_layer(*viewport, |renderer| {
-                    if let Some(line_y) = deferred_drop_marker_y {
-                        let line_color = theme.style(&self.class).color;
-                        let line_width = 2.0;
-                        let circle_outer_radius = 4.0;
-                        let circle_inner_radius = circle_outer_radius - line_width;
-
-                        // Draw line
-                        let marker_line_bounds = Rectangle {
-                            x: layout.bounds().x + self.padding.left + circle_inner_radius,
-                            y: line_y - line_width * 0.5,
-                            width: layout.bounds().width
-                                - self.padding.horizontal()
-                                - circle_inner_radius,
-                            height: line_width,
-                        };
-                        renderer.fill_quad(
-                            renderer::Quad {
-                                bounds: marker_line_bounds,
-                                ..renderer::Quad::default()
+                if selection.contains(key) {
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: item_layout.bounds() + offset,
+                            border: Border {
+                                radius: Radius::new(4.0),
+                                ..Border::default()
                             },
-                            line_color,
+                            ..renderer::Quad::default()
+                        },
+                        selection_color,
+                    );
+                }
+
+                if offset == Vector::ZERO {
+                    child.as_widget().draw(
+                        state,
+                        renderer,
+                        theme,
+                        style,
+                        item_layout,
+                        cursor,
+                        viewport,
+                    );
+                } else {
+                    renderer.with_translation(offset, |renderer| {
+                        child.as_widget().draw(
+                            state,
+                            renderer,
+                            theme,
+                            style,
+                            item_layout,
+                            cursor,
+                            viewport,
                         );
+                    });
+                }
+            }
 
-                        // Draw circle at the start of the line
-                        let marker_circle_bounds = Rectangle {
-                            x: layout.bounds().x + self.padding.left - circle_outer_radius,
-                            y: line_y - circle_outer_radius,
-                            width: circle_outer_radius * 2.0,
-                            height: circle_outer_radius * 2.0,
-                        };
+            if deferred_drop_marker.is_some() || focused_bounds.is_some() {
+                let style = theme.style(&self.class);
+                renderer.with_layer(*viewport, |renderer| {
+                    if let Some(bounds) = focused_bounds {
                         renderer.fill_quad(
                             renderer::Quad {
-                                bounds: marker_circle_bounds,
+                                bounds: bounds.expand(2.0),
                                 border: Border {
-                                    radius: Radius::new(circle_outer_radius),
-                                    color: line_color,
-                                    width: line_width,
+                                    radius: Radius::new(4.0),
+                                    color: style.focus,
+                                    width: 2.0,
                                 },
                                 ..renderer::Quad::default()
                             },
                             Color::TRANSPARENT,
                         );
                     }
-                    if let Some((child, state, layout)) = deferred_dragged_elem {
-                        renderer.with_translation(deferred_dragged_elem_translation, |renderer| {
-                            child
-                                .as_widget()
-                                .draw(state, renderer, theme, style, layout, cursor, viewport);
-                        });
+                    if let Some((start, end)) = deferred_drop_marker {
+                        let color = if drop_accepted {
+                            style.color
+                        } else {
+                            style.rejected
+                        };
+                        draw_drop_marker(renderer, start, end, color);
                     }
                 });
             }
@@ -740,16 +1608,110 @@ where
         renderer: &Renderer,
         translation: Vector,
     ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
-        overlay::from_children(&mut self.children, tree, layout, renderer, translation)
+        let state = tree.state.downcast_ref::<State<Key>>();
+        let dragged = match state.drag {
+            DragState::Dragged {
+                key,
+                origin,
+                position,
+                ..
+            } if self.drag_follow => {
+                Some((self.dragged_keys(&state.selection, key), position - origin))
+            }
+            _ => None,
+        };
+        let Some((dragged_keys, offset)) = dragged else {
+            return overlay::from_children(&mut self.children, tree, layout, renderer, translation);
+        };
+
+        let children = self
+            .children
+            .iter_mut()
+            .zip(&self.keys)
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .filter_map(|(((child, key), state), item_layout)| {
+                if dragged_keys.contains(key) {
+                    Some(overlay::Element::new(Box::new(DraggedOverlay {
+                        element: child,
+                        tree: state,
+                        bounds: item_layout.bounds() + translation + offset,
+                    })))
+                } else {
+                    child
+                        .as_widget_mut()
+                        .overlay(state, item_layout, renderer, translation)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        (!children.is_empty()).then(|| overlay::Group::with_children(children).overlay())
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+/// An overlay drawing a dragged child element of a [`Reorderable`] on top of everything else,
+/// so it is not clipped while following the cursor across containers.
+struct DraggedOverlay<'a, 'b, Message, Theme, Renderer> {
+    element: &'b Element<'a, Message, Theme, Renderer>,
+    tree: &'b mut Tree,
+    bounds: Rectangle,
+}
+
+impl<Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for DraggedOverlay<'_, '_, Message, Theme, Renderer>
+where
+    Renderer: iced::advanced::Renderer,
+{
+    fn layout(&mut self, renderer: &Renderer, _bounds: Size) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, self.bounds.size());
+
+        self.element
+            .as_widget()
+            .layout(self.tree, renderer, &limits)
+            .move_to(self.bounds.position())
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        self.element.as_widget().draw(
+            self.tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            &layout.bounds(),
+        );
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
 struct State<K>
 where
     K: Copy + PartialEq,
 {
     drag: DragState<K>,
+    /// The drop location of a child element dragged over from another container of the
+    /// drag [`Group`].
+    transfer_hover: Option<usize>,
+    /// The index of the focused child element, if the [`Reorderable`] is focused.
+    focus: Option<usize>,
+    /// The time of the last auto-scroll while dragging near an edge.
+    auto_scroll: Option<Instant>,
+    /// The animated offsets of the child elements.
+    offsets: Vec<animation::Offset<K>>,
+    /// The keys of the selected child elements.
+    selection: Vec<K>,
+    /// The key of the child element a shift-click selects a range from.
+    anchor: Option<K>,
+    /// The current state of the keyboard modifiers.
+    modifiers: keyboard::Modifiers,
 }
 
 impl<Key> Default for State<Key>
@@ -759,11 +1721,35 @@ where
     fn default() -> Self {
         Self {
             drag: DragState::Idle,
+            transfer_hover: None,
+            focus: None,
+            auto_scroll: None,
+            offsets: Vec::new(),
+            selection: Vec::new(),
+            anchor: None,
+            modifiers: keyboard::Modifiers::default(),
         }
     }
 }
 
-/// The current dragging state of a [`Column`].
+impl<Key> operation::Focusable for State<Key>
+where
+    Key: Copy + PartialEq,
+{
+    fn is_focused(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    fn focus(&mut self) {
+        self.focus = Some(self.focus.unwrap_or(0));
+    }
+
+    fn unfocus(&mut self) {
+        self.focus = None;
+    }
+}
+
+/// The current dragging state of a [`Reorderable`].
 #[derive(Default, Clone, Copy, PartialEq, Debug)]
 enum DragState<K>
 where
@@ -772,16 +1758,27 @@ where
     /// No child element is being dragged.
     #[default]
     Idle,
-    /// A [`Column`] child element is grabbed for dragging,
-    /// but has not been moved yet.
-    Grabbed { key: K, origin: Point },
-    /// A [`Column`] child element is being dragged.
+    /// A [`Reorderable`] child element is grabbed for dragging,
+    /// but has not been moved past the drag threshold or held long enough yet.
+    Grabbed {
+        key: K,
+        origin: Point,
+        press: Point,
+        time: Instant,
+    },
+    /// A [`Reorderable`] child element is being dragged.
     Dragged {
         key: K,
         origin: Point,
         position: Point,
         drop_location: usize,
     },
+    /// A [`Reorderable`] child element is picked up with the keyboard.
+    Picked {
+        key: K,
+        index: usize,
+        drop_location: usize,
+    },
 }
 
 impl<K> DragState<K>
@@ -793,45 +1790,50 @@ where
             Self::Idle => None,
             Self::Grabbed { key, .. } => Some(*key),
             Self::Dragged { key, .. } => Some(*key),
+            Self::Picked { key, .. } => Some(*key),
         }
     }
 
-    fn is_idle(&self) -> bool {
-        matches!(self, Self::Idle)
+    fn is_pointer_drag(&self) -> bool {
+        matches!(self, Self::Grabbed { .. } | Self::Dragged { .. })
     }
 
     fn last_position(&self) -> Option<Point> {
         match self {
-            Self::Idle => None,
-            Self::Grabbed { origin, .. } => Some(*origin),
+            Self::Idle | Self::Picked { .. } => None,
+            Self::Grabbed { press, .. } => Some(*press),
             Self::Dragged { position, .. } => Some(*position),
         }
     }
 
     fn drop_location(&self) -> Option<usize> {
         match self {
-            Self::Dragged { drop_location, .. } => Some(*drop_location),
+            Self::Dragged { drop_location, .. } | Self::Picked { drop_location, .. } => {
+                Some(*drop_location)
+            }
             _ => None,
         }
     }
 }
 
-impl<'a, Key, Message, Theme, Renderer> From<Column<'a, Key, Message, Theme, Renderer>>
+impl<'a, Key, Message, Theme, Renderer, D> From<Reorderable<'a, Key, Message, Theme, Renderer, D>>
     for Element<'a, Message, Theme, Renderer>
 where
     Key: Copy + PartialEq + 'static,
     Message: Clone + 'a,
     Theme: Catalog + 'a,
     Renderer: iced::advanced::Renderer + 'a,
+    D: Direction + 'a,
 {
-    fn from(widget: Column<'a, Key, Message, Theme, Renderer>) -> Self {
+    fn from(widget: Reorderable<'a, Key, Message, Theme, Renderer, D>) -> Self {
         Self::new(widget)
     }
 }
 
-/// Returns whether to propagate an [`Event`] to children of a [`Column`].
+/// Returns whether to propagate an [`Event`] to children of a [`Reorderable`].
 ///
-/// Will return `false` for mouse and touch events if a child element is being dragged.
+/// Will return `None` for mouse and touch movement if a child element is being dragged with
+/// the pointer.
 fn propagage_event_to_children<'a, Key>(
     drag_state: &DragState<Key>,
     event: &'a Event,
@@ -840,7 +1842,7 @@ fn propagage_event_to_children<'a, Key>(
 where
     Key: Copy + PartialEq,
 {
-    if !drag_state.is_idle() {
+    if drag_state.is_pointer_drag() {
         match event {
             Event::Touch(touch::Event::FingerMoved { .. })
             | Event::Mouse(mouse::Event::CursorMoved { .. }) => None,
@@ -851,40 +1853,115 @@ where
     }
 }
 
-/// Returns the index of the drop location among the children of a [`Column`]
-/// at given `position`.
-fn drop_location(layout: &Layout, position: Point) -> usize {
-    let mut index = 0;
-    for item_layout in layout.children() {
-        if position.y < item_layout.bounds().center_y() {
-            break;
+/// Returns the direction a keyboard key moves the focus or drop location in, if any.
+fn keyboard_step(key: &keyboard::Key) -> Option<isize> {
+    match key {
+        keyboard::Key::Named(keyboard::key::Named::ArrowUp | keyboard::key::Named::ArrowLeft) => {
+            Some(-1)
         }
-        index += 1;
+        keyboard::Key::Named(
+            keyboard::key::Named::ArrowDown | keyboard::key::Named::ArrowRight,
+        ) => Some(1),
+        _ => None,
     }
-    index
 }
 
-/// Returns Y-position for drop location marker on the `[Column]`.
-fn drop_location_marker_y(layout: &Layout, spacing: f32, drop_location: usize) -> Option<f32> {
-    if layout.children().count() == 0 {
-        None
-    } else if drop_location < layout.children().count() {
-        let child_bounds_below = layout.children().nth(drop_location).unwrap().bounds();
-        Some(child_bounds_below.y - spacing * 0.5)
+/// Moves the drop location of the child element picked up at `index` by `step`.
+///
+/// Dropping right after the child element leaves it in place just like dropping it right
+/// before, so that location is skipped.
+fn step_drop_location(index: usize, drop_location: usize, step: isize, count: usize) -> usize {
+    let new_location = drop_location.saturating_add_signed(step).min(count);
+    if new_location != index + 1 {
+        new_location
+    } else if step < 0 {
+        index
+    } else if index + 2 <= count {
+        index + 2
     } else {
-        let last_child_bounds = layout.children().last().unwrap().bounds();
-        Some(last_child_bounds.y + last_child_bounds.height + spacing * 0.5)
+        drop_location
     }
 }
 
-/// The appearance of of a [`Column`].
+/// Returns the drop location marker line for a [`Reorderable`] without children.
+fn empty_drop_marker(layout: &Layout<'_>, padding: Padding) -> Option<(Point, Point)> {
+    let bounds = layout.bounds();
+    let line_y = bounds.y + padding.top;
+    Some((
+        Point::new(bounds.x + padding.left, line_y),
+        Point::new(bounds.x + bounds.width - padding.right, line_y),
+    ))
+}
+
+/// Draws a drop location marker line from `start` to `end`, with a circle at its start.
+fn draw_drop_marker<Renderer>(renderer: &mut Renderer, start: Point, end: Point, color: Color)
+where
+    Renderer: iced::advanced::Renderer,
+{
+    let line_width = 2.0;
+    let circle_outer_radius = 4.0;
+    let circle_inner_radius = circle_outer_radius - line_width;
+
+    // Draw line
+    let marker_line_bounds = if start.y == end.y {
+        Rectangle {
+            x: start.x + circle_inner_radius,
+            y: start.y - line_width * 0.5,
+            width: end.x - start.x - circle_inner_radius,
+            height: line_width,
+        }
+    } else {
+        Rectangle {
+            x: start.x - line_width * 0.5,
+            y: start.y + circle_inner_radius,
+            width: line_width,
+            height: end.y - start.y - circle_inner_radius,
+        }
+    };
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds: marker_line_bounds,
+            ..renderer::Quad::default()
+        },
+        color,
+    );
+
+    // Draw circle at the start of the line
+    let marker_circle_bounds = Rectangle {
+        x: start.x - circle_outer_radius,
+        y: start.y - circle_outer_radius,
+        width: circle_outer_radius * 2.0,
+        height: circle_outer_radius * 2.0,
+    };
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds: marker_circle_bounds,
+            border: Border {
+                radius: Radius::new(circle_outer_radius),
+                color,
+                width: line_width,
+            },
+            ..renderer::Quad::default()
+        },
+        Color::TRANSPARENT,
+    );
+}
+
+/// The appearance of of a [`Reorderable`].
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Style {
     /// The color of the drop position marker line indicating drop placement.
     pub color: Color,
+    /// The color of the outline around the focused child element.
+    pub focus: Color,
+    /// The background color of selected child elements.
+    pub selection: Color,
+    /// The color of the drop position marker line at a location where the dragged child
+    /// element cannot be dropped.
+    pub rejected: Color,
 }
 
-/// The theme catalog of a [`Column`].
+/// The theme catalog of a [`Reorderable`].
 pub trait Catalog: Sized {
     /// The item class of the [`Catalog`].
     type Class<'a>;
@@ -896,7 +1973,7 @@ pub trait Catalog: Sized {
     fn style(&self, class: &Self::Class<'_>) -> Style;
 }
 
-/// A styling function for a [`Column`].
+/// A styling function for a [`Reorderable`].
 ///
 /// This is just a boxed closure: `Fn(&Theme, Status) -> Style`.
 pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme) -> Style + 'a>;
@@ -913,9 +1990,12 @@ impl Catalog for Theme {
     }
 }
 
-/// The default style of a [`Column`].
+/// The default style of a [`Reorderable`].
 pub fn default(theme: &Theme) -> Style {
     Style {
         color: theme.palette().primary,
+        focus: theme.extended_palette().primary.weak.color,
+        selection: theme.extended_palette().primary.base.color.scale_alpha(0.25),
+        rejected: theme.palette().danger,
     }
 }
@@ -0,0 +1,353 @@
+/// An ordered collection of keyed items that applies the messages of a [`Reorderable`].
+///
+/// Keys stay attached to their items while they move around, so they can be used as the keys
+/// of the [`Reorderable`] children instead of positions. Keys should be unique across every
+/// [`List`] items are transferred between.
+///
+/// [`Reorderable`]: crate::Reorderable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct List<Key, T> {
+    items: Vec<(Key, T)>,
+    grabbed: Option<Key>,
+}
+
+impl<Key, T> List<Key, T>
+where
+    Key: Copy + PartialEq,
+{
+    /// Creates an empty [`List`].
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            grabbed: None,
+        }
+    }
+
+    /// Returns the number of items in the [`List`].
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the [`List`] has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Appends an item with the given key.
+    pub fn push(&mut self, key: Key, item: T) {
+        self.items.push((key, item));
+    }
+
+    /// Inserts an item with the given key at `index`, or at the end if `index` is out of bounds.
+    pub fn insert(&mut self, index: usize, key: Key, item: T) {
+        self.items.insert(index.min(self.items.len()), (key, item));
+    }
+
+    /// Removes the item with the given key and returns it, if any.
+    pub fn remove(&mut self, key: Key) -> Option<T> {
+        let index = self.position(key)?;
+        if self.grabbed == Some(key) {
+            self.grabbed = None;
+        }
+        Some(self.items.remove(index).1)
+    }
+
+    /// Returns the position of the item with the given key, if any.
+    pub fn position(&self, key: Key) -> Option<usize> {
+        self.items.iter().position(|(other, _)| *other == key)
+    }
+
+    /// Returns the item with the given key, if any.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        self.items
+            .iter()
+            .find(|(other, _)| *other == key)
+            .map(|(_, item)| item)
+    }
+
+    /// Returns the item with the given key mutably, if any.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        self.items
+            .iter_mut()
+            .find(|(other, _)| *other == key)
+            .map(|(_, item)| item)
+    }
+
+    /// Returns an iterator over the keys and items, in order.
+    ///
+    /// This is meant to be mapped into the children of a [`Reorderable`].
+    ///
+    /// [`Reorderable`]: crate::Reorderable
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Key, &T)> + ExactSizeIterator {
+        self.items.iter().map(|(key, item)| (*key, item))
+    }
+
+    /// Returns an iterator over the keys, in order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = Key> + ExactSizeIterator {
+        self.items.iter().map(|(key, _)| *key)
+    }
+
+    /// Marks the item with the given key as grabbed, as published by
+    /// [`Reorderable::on_grab`] or [`Reorderable::on_drag`].
+    ///
+    /// [`Reorderable::on_grab`]: crate::Reorderable::on_grab
+    /// [`Reorderable::on_drag`]: crate::Reorderable::on_drag
+    pub fn grab(&mut self, key: Key) {
+        self.grabbed = self.position(key).map(|_| key);
+    }
+
+    /// Returns the key of the grabbed item, if any.
+    pub fn grabbed(&self) -> Option<Key> {
+        self.grabbed
+    }
+
+    /// Releases the grabbed item without moving it, as published by
+    /// [`Reorderable::on_cancel`].
+    ///
+    /// [`Reorderable::on_cancel`]: crate::Reorderable::on_cancel
+    pub fn cancel(&mut self) {
+        self.grabbed = None;
+    }
+
+    /// Moves the item with the given key to `drop_location`, as published by
+    /// [`Reorderable::on_drop`].
+    ///
+    /// The drop location is an index among the items _before_ the move, so dropping an item
+    /// right before or after itself leaves it in place.
+    ///
+    /// Returns the new position of the item, or `None` if there is no item with the given key.
+    ///
+    /// [`Reorderable::on_drop`]: crate::Reorderable::on_drop
+    pub fn drop_at(&mut self, key: Key, drop_location: usize) -> Option<usize> {
+        self.grabbed = None;
+        let index = self.position(key)?;
+        let item = self.items.remove(index);
+        let new_index = if drop_location > index {
+            drop_location - 1
+        } else {
+            drop_location
+        }
+        .min(self.items.len());
+        self.items.insert(new_index, item);
+        Some(new_index)
+    }
+
+    /// Moves the items with the given keys to `drop_location`, keeping their relative order,
+    /// as published by [`Reorderable::on_drop_many`].
+    ///
+    /// Returns the new position of the first moved item, or `None` if none of the keys are in
+    /// the [`List`].
+    ///
+    /// [`Reorderable::on_drop_many`]: crate::Reorderable::on_drop_many
+    pub fn drop_many_at(&mut self, keys: &[Key], drop_location: usize) -> Option<usize> {
+        self.grabbed = None;
+        let drop_location = drop_location.min(self.items.len());
+        let before = self.items[..drop_location]
+            .iter()
+            .filter(|(key, _)| keys.contains(key))
+            .count();
+        let (moved, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|(key, _)| keys.contains(key));
+        self.items = rest;
+        if moved.is_empty() {
+            return None;
+        }
+        let new_index = drop_location - before;
+        let _ = self.items.splice(new_index..new_index, moved);
+        Some(new_index)
+    }
+
+    /// Moves the item with the given key into the `target` [`List`] at `drop_location`, as
+    /// published by [`Reorderable::on_transfer`].
+    ///
+    /// Returns the position of the item in `target`, or `None` if there is no item with the
+    /// given key.
+    ///
+    /// [`Reorderable::on_transfer`]: crate::Reorderable::on_transfer
+    pub fn transfer_to(
+        &mut self,
+        target: &mut Self,
+        key: Key,
+        drop_location: usize,
+    ) -> Option<usize> {
+        self.grabbed = None;
+        target.grabbed = None;
+        let index = self.position(key)?;
+        let item = self.items.remove(index);
+        let new_index = drop_location.min(target.items.len());
+        target.items.insert(new_index, item);
+        Some(new_index)
+    }
+}
+
+impl<Key, T> Default for List<Key, T>
+where
+    Key: Copy + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Key, T> FromIterator<(Key, T)> for List<Key, T>
+where
+    Key: Copy + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = (Key, T)>>(iter: I) -> Self {
+        Self {
+            items: iter.into_iter().collect(),
+            grabbed: None,
+        }
+    }
+}
+
+impl<Key, T> Extend<(Key, T)> for List<Key, T>
+where
+    Key: Copy + PartialEq,
+{
+    fn extend<I: IntoIterator<Item = (Key, T)>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::List;
+
+    use proptest::prelude::*;
+    use proptest::sample::{Index, subsequence};
+
+    /// A [`List`] of `len` items keyed `0..len`, each holding its own key.
+    fn list(len: usize) -> List<usize, usize> {
+        (0..len).map(|key| (key, key)).collect()
+    }
+
+    /// The length of a [`list`] and a non-empty selection of its keys, in order.
+    fn selection() -> impl Strategy<Value = (usize, Vec<usize>)> {
+        (1..32usize).prop_flat_map(|len| {
+            (
+                Just(len),
+                subsequence((0..len).collect::<Vec<_>>(), 1..=len),
+            )
+        })
+    }
+
+    fn sorted(keys: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut keys: Vec<usize> = keys.collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Checks that every item still holds its own key.
+    fn assert_consistent(list: &List<usize, usize>) {
+        for (key, item) in list.iter() {
+            assert_eq!(key, *item);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn drop_is_a_permutation(len in 1..32usize, key: Index, drop_location in 0..40usize) {
+            let mut list = list(len);
+            let key = key.index(len);
+
+            let new_index = list.drop_at(key, drop_location).unwrap();
+
+            assert_consistent(&list);
+            prop_assert_eq!(sorted(list.keys()), (0..len).collect::<Vec<_>>());
+            prop_assert_eq!(list.position(key), Some(new_index));
+            let expected = if drop_location > key { drop_location - 1 } else { drop_location };
+            prop_assert_eq!(new_index, expected.min(len - 1));
+
+            let others: Vec<usize> = list.keys().filter(|other| *other != key).collect();
+            prop_assert_eq!(others, (0..len).filter(|other| *other != key).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn drop_next_to_itself_keeps_the_order(len in 1..32usize, key: Index, after: bool) {
+            let mut list = list(len);
+            let key = key.index(len);
+
+            let _ = list.drop_at(key, key + usize::from(after));
+
+            prop_assert_eq!(list.keys().collect::<Vec<_>>(), (0..len).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn drop_many_is_a_permutation(
+            (len, keys) in selection(),
+            drop_location in 0..40usize,
+        ) {
+            let mut list = list(len);
+
+            let new_index = list.drop_many_at(&keys, drop_location).unwrap();
+
+            assert_consistent(&list);
+            prop_assert_eq!(sorted(list.keys()), (0..len).collect::<Vec<_>>());
+            let order: Vec<usize> = list.keys().collect();
+            prop_assert_eq!(&order[new_index..new_index + keys.len()], keys.as_slice());
+
+            let before = keys.iter().filter(|key| **key < drop_location.min(len)).count();
+            prop_assert_eq!(new_index, drop_location.min(len) - before);
+
+            let others: Vec<usize> = order.iter().copied().filter(|key| !keys.contains(key)).collect();
+            prop_assert_eq!(others, (0..len).filter(|key| !keys.contains(key)).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn transfer_moves_one_item(
+            len in 1..32usize,
+            target_len in 0..32usize,
+            key: Index,
+            drop_location in 0..40usize,
+        ) {
+            let mut source = list(len);
+            let mut target: List<usize, usize> = (len..len + target_len).map(|key| (key, key)).collect();
+            let key = key.index(len);
+
+            let new_index = source.transfer_to(&mut target, key, drop_location).unwrap();
+
+            assert_consistent(&source);
+            assert_consistent(&target);
+            prop_assert_eq!(source.len(), len - 1);
+            prop_assert_eq!(target.len(), target_len + 1);
+            prop_assert_eq!(source.position(key), None);
+            prop_assert_eq!(target.position(key), Some(new_index));
+            prop_assert_eq!(new_index, drop_location.min(target_len));
+            prop_assert_eq!(
+                sorted(source.keys().chain(target.keys())),
+                (0..len + target_len).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn unknown_keys_leave_the_list_unchanged(len in 0..32usize, drop_location in 0..40usize) {
+            let mut list = list(len);
+            let mut target = List::new();
+
+            prop_assert_eq!(list.drop_at(len, drop_location), None);
+            prop_assert_eq!(list.drop_many_at(&[len, len + 1], drop_location), None);
+            prop_assert_eq!(list.transfer_to(&mut target, len, drop_location), None);
+            prop_assert_eq!(list.keys().collect::<Vec<_>>(), (0..len).collect::<Vec<_>>());
+            prop_assert!(target.is_empty());
+        }
+    }
+
+    #[test]
+    fn grab_is_cleared_by_cancel_and_drop() {
+        let mut list = list(3);
+
+        list.grab(1);
+        assert_eq!(list.grabbed(), Some(1));
+        list.cancel();
+        assert_eq!(list.grabbed(), None);
+
+        list.grab(1);
+        let _ = list.drop_at(1, 0);
+        assert_eq!(list.grabbed(), None);
+
+        list.grab(7);
+        assert_eq!(list.grabbed(), None);
+    }
+}
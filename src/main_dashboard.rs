@@ -1,3 +1,4 @@
+use iced::advanced::widget;
 use iced::border;
 use iced::mouse;
 use iced::widget::pane_grid;
@@ -7,29 +8,76 @@ use iced::widget::PaneGrid;
 use iced::widget::Text;
 use iced::widget::{
     button, canvas, center, checkbox, column, container,
-    horizontal_space, row,
-    text,
+    horizontal_space, mouse_area, opaque, pick_list, row, scrollable, slider, stack,
+    text, text_input,
 };
 use iced::Color;
 use iced::Padding;
 use iced::Size;
+use iced::time::{self, Duration, Instant};
+use iced_playground::List;
 use iced::{
     Center, Element, Fill, Font, Length, Point, Rectangle, Renderer,
     Subscription, Theme,
 };
-use serde::Deserialize;
-use serde::Serialize;
 
+mod alerts;
+mod commands;
+mod modules;
 mod nats;
+mod replay;
+mod sparkline;
+mod store;
+mod stream_data;
 mod ws_handler;
 
+use alerts::{Alerts, RuleId};
+use commands::{Command, RequestId};
+use modules::Module;
+use replay::Source;
+use sparkline::Charts;
+use store::{Instrument, Store};
+use stream_data::{Diagnostic, Diagnostics, StreamData};
+
 pub fn main() -> iced::Result {
+    if let Err(error) = replay::init() {
+        eprintln!("{error}");
+        eprintln!("Usage: [--record <file> | --replay <file> [--speed <factor>]]");
+        std::process::exit(2);
+    }
+    if let Err(error) = nats::config::init() {
+        eprintln!("Invalid NATS configuration: {error}");
+        std::process::exit(1);
+    }
+    let alerts = match Alerts::load() {
+        Ok(alerts) => alerts,
+        Err(error) => {
+            eprintln!("Invalid alert rules: {error}");
+            std::process::exit(1);
+        }
+    };
+
     iced::application("Example - Iced", Layout::update, Layout::view)
         .subscription(Layout::subscription)
         .theme(Layout::theme)
-        .run()
+        .run_with(move || (Layout { alerts, ..Layout::default() }, iced::Task::none()))
 }
 
+/// How long the store keeps the snapshots of an instrument, long enough for every chart window.
+const HISTORY_RETENTION: Duration = sparkline::SESSION_LENGTH;
+
+/// How many decoding problems the diagnostics log keeps.
+const DIAGNOSTICS_CAPACITY: usize = 100;
+
+/// How many sent commands the command log keeps.
+const COMMAND_LOG_CAPACITY: usize = 20;
+
+/// How often the borders of modules with unacknowledged alerts flash.
+const FLASH_INTERVAL: Duration = Duration::from_millis(500);
+
+const MODULES: &str = "modules";
+const SIDEBAR: &str = "sidebar";
+
 const PANE_ID_COLOR_UNFOCUSED: Color = Color::from_rgb(
     0xFF as f32 / 255.0,
     0xC7 as f32 / 255.0,
@@ -45,12 +93,29 @@ const PANE_ID_COLOR_FOCUSED: Color = Color::from_rgb(
 struct Layout{
     explain: bool,
     boards: bool,
-    stream_data: StreamData,
+    store: Store,
+    charts: Charts,
+    alerts: Alerts,
+    alert_draft: alerts::Draft,
+    show_alerts: bool,
+    flash: bool,
+    commands: Option<commands::Connection>,
+    command_status: Option<String>,
+    command_draft: commands::Draft,
+    confirming: Option<Command>,
+    requests: Vec<commands::Request>,
+    next_request: RequestId,
+    nats_status: ws_handler::Event,
+    replay: Option<replay::State>,
+    last_message: Option<Instant>,
+    now: Instant,
+    diagnostics: Diagnostics,
+    show_diagnostics: bool,
     panes: pane_grid::State<Pane>,
     panes_created: usize,
     focus: Option<pane_grid::Pane>,
-    dragged: Option<usize>,
-    items: Vec<String>,
+    items: List<usize, Module>,
+    sidebar_items: List<usize, Module>,
 }
 
 impl Default for Layout {
@@ -60,12 +125,33 @@ impl Default for Layout {
         Self {
             explain: false,
             boards: true,
-            stream_data: StreamData::default(),
+            store: Store::new(HISTORY_RETENTION),
+            charts: Charts::default(),
+            alerts: Alerts::default(),
+            alert_draft: alerts::Draft::default(),
+            show_alerts: false,
+            flash: false,
+            commands: None,
+            command_status: None,
+            command_draft: commands::Draft::default(),
+            confirming: None,
+            requests: Vec::new(),
+            next_request: 0,
+            nats_status: ws_handler::Event::Connecting,
+            replay: replay_state(),
+            last_message: None,
+            now: Instant::now(),
+            diagnostics: Diagnostics::new(DIAGNOSTICS_CAPACITY),
+            show_diagnostics: false,
             panes,
             panes_created: 0,
             focus: None,
-            dragged: None,
-            items: vec!["Perp Risk Metrics Module".to_string(), "Perp Performance Metrics Module".to_string(), "Positions Module".to_string()],
+            items: List::from_iter([
+                (0, Module::Risk),
+                (1, Module::Performance),
+                (2, Module::Volume),
+            ]),
+            sidebar_items: List::new(),
         }
     }
 }
@@ -86,37 +172,33 @@ impl Pane {
 }
 
 
-#[derive(Default, Debug, Serialize, Deserialize)]
-struct StreamData {
-    name: String,
-    currency: String,
-    index_price: f64,
-    unrealized_pnl: f64,
-    spread: f64,
-    long_ave_entry_price: f64,
-    short_ave_entry_price: f64,
-    long_size: f64,
-    long_usd_notional: f64,
-    short_size: f64,
-    short_usd_notional: f64,
-    dtd_pnl: f64,
-    wtd_pnl: f64,
-    mtd_pnl: f64,
-    dtd_volume_maker: f64,
-    dtd_volume_taker: f64,
-    wtd_volume_maker: f64,
-    wtd_volume_taker: f64,
-    mtd_volume_maker: f64,
-    mtd_volume_taker: f64,
-}
-
-
-
 #[derive(Debug, Clone)]
 pub enum Message {
     ExplainToggled(bool),
     ToggleBoards,
-    NatsMessageReceived { payload: Vec<u8> },
+    /// A snapshot and when it was received, or recorded if it is replayed.
+    NatsMessageReceived(StreamData, Instant),
+    Nats(ws_handler::Event),
+    Replay(replay::Event),
+    ReplayCommand(replay::Command),
+    Diagnostic(Diagnostic),
+    ToggleDiagnostics,
+    ClearDiagnostics,
+    Tick(Instant),
+    ChartFieldSelected(sparkline::Field),
+    ChartWindowSelected(sparkline::Window),
+    ToggleAlerts,
+    AlertDraftChanged(alerts::Draft),
+    AddAlertRule(alerts::Rule),
+    RemoveAlertRule(RuleId),
+    AcknowledgeAlert(RuleId, Instrument),
+    AcknowledgeAllAlerts,
+    Flash,
+    Commands(commands::Event),
+    CommandDraftChanged(commands::Draft),
+    ConfirmCommand(Command),
+    SendCommand,
+    CancelCommand,
     Clicked(pane_grid::Pane),
     Dragged(pane_grid::DragEvent),
     Resized(pane_grid::ResizeEvent),
@@ -129,6 +211,8 @@ pub enum Message {
     Drag(usize, usize),
     Drop(usize, usize),
     Cancel(usize),
+    SidebarDrop(usize, usize),
+    Transfer(usize, widget::Id, widget::Id, usize),
 }
 
 impl Layout {
@@ -138,12 +222,29 @@ impl Layout {
         Layout {
             explain: false,
             boards: false,
-            stream_data: StreamData::default(),
+            store: Store::new(HISTORY_RETENTION),
+            charts: Charts::default(),
+            alerts: Alerts::default(),
+            alert_draft: alerts::Draft::default(),
+            show_alerts: false,
+            flash: false,
+            commands: None,
+            command_status: None,
+            command_draft: commands::Draft::default(),
+            confirming: None,
+            requests: Vec::new(),
+            next_request: 0,
+            nats_status: ws_handler::Event::Connecting,
+            replay: replay_state(),
+            last_message: None,
+            now: Instant::now(),
+            diagnostics: Diagnostics::new(DIAGNOSTICS_CAPACITY),
+            show_diagnostics: false,
             panes,
             panes_created: 0,
             focus: None,
-            dragged: None,
-            items: Vec::new(),
+            items: List::new(),
+            sidebar_items: List::new(),
         }
     }
 
@@ -165,9 +266,97 @@ impl Layout {
             Message::ToggleBoards => {
                 self.boards = !self.boards;
             }
-            Message::NatsMessageReceived { payload } => {
-                self.stream_data = serde_json::from_slice(&payload).unwrap();
-                // println!("Stream Data: {:?}", self.stream_data);
+            Message::Nats(status) => {
+                self.nats_status = status;
+            }
+            Message::Replay(event) => {
+                if let Some(replay) = &mut self.replay {
+                    replay.update(event);
+                }
+            }
+            Message::ReplayCommand(command) => {
+                if let Some(replay) = &mut self.replay {
+                    replay.send(command);
+                }
+            }
+            Message::Tick(now) => {
+                self.now = now;
+            }
+            Message::Diagnostic(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+            }
+            Message::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+            }
+            Message::ClearDiagnostics => {
+                self.diagnostics.clear();
+            }
+            Message::ChartFieldSelected(field) => {
+                self.charts.select_field(field);
+            }
+            Message::ChartWindowSelected(window) => {
+                self.charts.select_window(window);
+            }
+            Message::ToggleAlerts => {
+                self.show_alerts = !self.show_alerts;
+            }
+            Message::AlertDraftChanged(draft) => {
+                self.alert_draft = draft;
+            }
+            Message::AddAlertRule(rule) => {
+                let _ = self.alerts.add_rule(rule);
+                self.save_alerts();
+            }
+            Message::RemoveAlertRule(id) => {
+                self.alerts.remove_rule(id);
+                self.save_alerts();
+            }
+            Message::AcknowledgeAlert(rule, instrument) => {
+                self.alerts.acknowledge(rule, &instrument);
+            }
+            Message::AcknowledgeAllAlerts => {
+                self.alerts.acknowledge_all();
+            }
+            Message::Flash => {
+                self.flash = !self.flash;
+            }
+            Message::Commands(event) => match event {
+                commands::Event::Connected(connection) => {
+                    self.commands = Some(connection);
+                    self.command_status = None;
+                }
+                commands::Event::Disconnected(reason) => {
+                    self.commands = None;
+                    self.command_status = Some(reason);
+                }
+                commands::Event::Replied(id, reply) => {
+                    let request = self.requests.iter_mut().find(|request| request.id == id);
+                    if let Some(request) = request {
+                        request.reply = Some(reply);
+                    }
+                }
+            },
+            Message::CommandDraftChanged(draft) => {
+                self.command_draft = draft;
+            }
+            Message::ConfirmCommand(command) => {
+                self.confirming = Some(command);
+            }
+            Message::SendCommand => {
+                if let Some(command) = self.confirming.take() {
+                    self.send_command(command);
+                }
+            }
+            Message::CancelCommand => {
+                self.confirming = None;
+            }
+            Message::NatsMessageReceived(stream_data, time) => {
+                let instrument = Instrument::of(&stream_data);
+                self.charts.prepare(&instrument);
+                self.charts.invalidate(&instrument);
+                let _ = self.alerts.evaluate(&stream_data, time);
+                self.store.insert(stream_data, time);
+                self.last_message = Some(time);
             }
             Message::Clicked(pane) => {
                 self.focus = Some(pane);
@@ -228,44 +417,108 @@ impl Layout {
                 self.panes.restore();
             }
             Message::Drag(key, _loc) => {
-                self.dragged = Some(key);
+                self.items.grab(key);
             }
             Message::Drop(key, loc) => {
-                self.drop_item(key, loc);
-                self.dragged = None;
+                let _ = self.items.drop_at(key, loc);
             }
             Message::Cancel(_key) => {
-                self.dragged = None;
+                self.items.cancel();
+            }
+            Message::SidebarDrop(key, loc) => {
+                let _ = self.sidebar_items.drop_at(key, loc);
+            }
+            Message::Transfer(key, from, to, loc) => {
+                self.transfer_item(key, &from, &to, loc);
             }
         }
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::run(ws_handler::connect).map(|event| {
-            // eprintln!("Received event from WebSocket: {:?}", event);
-            event
-        })
+        // A replay runs without a network, so there is no one to send commands to.
+        let (feed, commands) = match replay::get() {
+            Source::Live { .. } => (
+                Subscription::run(ws_handler::connect),
+                Subscription::run(commands::connect),
+            ),
+            Source::Replay { .. } => (Subscription::run(replay::connect), Subscription::none()),
+        };
+
+        Subscription::batch([
+            feed,
+            commands,
+            time::every(Duration::from_secs(1)).map(Message::Tick),
+            if self.alerts.unacknowledged() > 0 {
+                time::every(FLASH_INTERVAL).map(|_| Message::Flash)
+            } else {
+                Subscription::none()
+            },
+        ])
     }
 
-    fn drop_item(&mut self, key: usize, mut loc: usize) {
-        if key > self.items.len() || key == loc || key + 1 == loc {
-            return;
+    fn send_command(&mut self, command: Command) {
+        let id = self.next_request;
+        self.next_request += 1;
+
+        let reply = match &mut self.commands {
+            Some(connection) => connection.send(id, command.clone()).err().map(Err),
+            None => Some(Err("not connected".to_string())),
+        };
+
+        if self.requests.len() == COMMAND_LOG_CAPACITY {
+            let _ = self.requests.remove(0);
         }
-        if loc > key {
-            loc -= 1;
+        self.requests.push(commands::Request {
+            id,
+            command,
+            sent: Instant::now(),
+            reply,
+        });
+    }
+
+    /// Returns the current time of the feed: the recorded time a replay has reached, or the
+    /// time of the last tick.
+    fn feed_now(&self) -> Instant {
+        self.replay
+            .as_ref()
+            .and_then(|replay| replay.clock(self.now))
+            .unwrap_or(self.now)
+    }
+
+    fn save_alerts(&mut self) {
+        if let Err(error) = self.alerts.save() {
+            eprintln!("Cannot save alert rules: {error}");
         }
-        let slot = self.items.remove(key);
-        if loc < self.items.len() {
-            self.items.insert(loc, slot);
+    }
+
+    fn transfer_item(&mut self, key: usize, from: &widget::Id, to: &widget::Id, loc: usize) {
+        let (source, target) = if *from == widget::Id::new(MODULES) && *to == widget::Id::new(SIDEBAR) {
+            (&mut self.items, &mut self.sidebar_items)
+        } else if *from == widget::Id::new(SIDEBAR) && *to == widget::Id::new(MODULES) {
+            (&mut self.sidebar_items, &mut self.items)
         } else {
-            self.items.push(slot);
-        }
+            return;
+        };
+        let _ = source.transfer_to(target, key, loc);
     }
 
     fn view(&self) -> Element<Message> {
         let header = row![
             text("Example Header").size(20).font(Font::MONOSPACE),
             horizontal_space(),
+            match &self.replay {
+                Some(replay) => self.view_replay(replay),
+                None => self.view_nats_status(),
+            },
+            button(text(format!("Alerts ({})", self.alerts.unacknowledged())))
+                .style(if self.alerts.unacknowledged() > 0 {
+                    button::danger
+                } else {
+                    button::primary
+                })
+                .on_press(Message::ToggleAlerts),
+            button(text(format!("Diagnostics ({})", self.diagnostics.len())))
+                .on_press(Message::ToggleDiagnostics),
             button("Board Management").on_press(Message::ToggleBoards),
             checkbox("Explain", self.explain)
                 .on_toggle(Message::ExplainToggled),
@@ -303,39 +556,67 @@ impl Layout {
         };
 
         let reorderable_items =
-            iced_playground::Column::from_iter(self.items.iter().enumerate().map(|(index, item)| {
-                let item_style = if Some(index) == self.dragged {
+            iced_playground::Column::from_iter(self.items.iter().map(|(key, item)| {
+                let item_style = if Some(key) == self.items.grabbed() {
                     style::item_dragged
+                } else if self.flash && self.alerts.is_raised(item.fields()) {
+                    style::item_alert
                 } else {
                     style::item_idle
                 };
-                let content = Container::new(Text::new(item))
-                    .align_x(Center)
-                    .align_y(Center)
-                    .height(Fill)
+                let content = Container::new(item.view(&self.store))
                     .width(Fill)
                     .padding(ITEM_PADDING)
                     .style(item_style);
 
-                (index, content.into())
+                (key, content.into())
             }))
             .spacing(10)
             .padding(Padding::default())
             .on_drag(|key, index| Message::Drag(key, index))
             .on_drop(|key, index| Message::Drop(key, index))
             .on_cancel(|key| Message::Cancel(key))
+            .on_transfer(Message::Transfer)
+            .id(MODULES)
+            .drag_group(MODULES)
+            .drag_threshold(4)
             .drop_position_marker(true);
 
 
-        let content = column!(reorderable_items).spacing(24).padding(12);
+        let content = column![
+            self.view_instruments(),
+            self.view_charts(),
+            self.view_commands(),
+            reorderable_items,
+        ]
+        .spacing(24)
+        .padding(12);
 
         let pane_grid = Container::new(content).center(Length::Fill);
 
         // let pane_grid = create_pane(&self);
         // ==========================================================
 
+        let sidebar_items =
+            iced_playground::Column::from_iter(self.sidebar_items.iter().map(|(key, item)| {
+                let content = Container::new(Text::new(item.title()))
+                    .width(Fill)
+                    .padding(ITEM_PADDING)
+                    .style(style::item_idle);
+
+                (key, content.into())
+            }))
+            .spacing(10)
+            .width(Fill)
+            .height(100)
+            .on_drop(Message::SidebarDrop)
+            .on_transfer(Message::Transfer)
+            .drag_threshold(4)
+            .id(SIDEBAR)
+            .drag_group(MODULES);
+
         let sidebar = container(
-            column!["Sidebar!", square(50), square(50)]
+            column!["Sidebar!", sidebar_items, square(50), square(50)]
                 .spacing(40)
                 .padding(10)
                 .width(200)
@@ -356,17 +637,417 @@ impl Layout {
                 .border(border::color(palette.background.strong.color).width(4))
         });
 
-        column![row![header], content]
+        let alerts = self.show_alerts.then(|| self.view_alerts());
+        let diagnostics = self.show_diagnostics.then(|| self.view_diagnostics());
+
+        let dashboard = column![row![header], content]
+            .push_maybe(alerts)
+            .push_maybe(diagnostics)
+            .spacing(10)
+            .padding(20);
+
+        match &self.confirming {
+            Some(command) => modal(dashboard, view_confirmation(command), Message::CancelCommand),
+            None => dashboard.into(),
+        }
+    }
+
+    fn view_nats_status(&self) -> Element<Message> {
+        let status = text(self.nats_status.to_string()).style(match self.nats_status {
+            ws_handler::Event::Connected => text::success,
+            ws_handler::Event::Stalled
+            | ws_handler::Event::Disconnected { .. }
+            | ws_handler::Event::Closed => text::danger,
+            ws_handler::Event::Connecting | ws_handler::Event::Reconnecting { .. } => text::secondary,
+        });
+
+        let age = match self.last_message {
+            Some(last_message) => format!(
+                "last message {}s ago",
+                self.feed_now().saturating_duration_since(last_message).as_secs()
+            ),
+            None => "no messages yet".to_string(),
+        };
+
+        row![status, text(age).size(14)]
             .spacing(10)
-            .padding(20)
+            .align_y(Center)
             .into()
     }
 
+    fn view_replay<'a>(&'a self, replay: &'a replay::State) -> Element<'a, Message> {
+        if let Some(error) = &replay.error {
+            return text(format!("Cannot replay: {error}")).style(text::danger).into();
+        }
+
+        let pause = if replay.paused {
+            button("Resume").on_press(Message::ReplayCommand(replay::Command::Resume))
+        } else {
+            button("Pause").on_press(Message::ReplayCommand(replay::Command::Pause))
+        };
+
+        let seek = slider(
+            0.0..=replay.duration.as_secs_f64(),
+            replay.position.as_secs_f64(),
+            |position| {
+                Message::ReplayCommand(replay::Command::Seek(Duration::from_secs_f64(position)))
+            },
+        )
+        .step(0.1)
+        .width(200);
+
+        let status = if replay.finished { "finished" } else { "replaying" };
+
+        row![
+            text(format!("{status} {}", replay.path.display())).size(14),
+            pause,
+            pick_list(&replay::SPEEDS[..], Some(replay.speed), |speed| {
+                Message::ReplayCommand(replay::Command::Speed(speed))
+            }),
+            seek,
+            text(format!(
+                "{:.0}s / {:.0}s",
+                replay.position.as_secs_f32(),
+                replay.duration.as_secs_f32()
+            ))
+            .size(14),
+        ]
+        .spacing(10)
+        .align_y(Center)
+        .into()
+    }
+
+    fn view_instruments(&self) -> Element<Message> {
+        if self.store.is_empty() {
+            return text("Waiting for market data...").into();
+        }
+
+        let cell = |content: String| text(content).font(Font::MONOSPACE).width(Fill);
+
+        let header = row![
+            cell("Instrument".to_string()),
+            cell("Index price".to_string()),
+            cell("Unrealized PnL".to_string()),
+            cell("Spread".to_string()),
+            cell("Updated".to_string()),
+        ]
+        .spacing(10);
+
+        let rows = self.store.iter().map(|(instrument, history)| {
+            let latest = history.latest();
+            let age = self.feed_now().saturating_duration_since(latest.time).as_secs();
+
+            row![
+                cell(instrument.to_string()),
+                cell(format!("{:.2}", latest.data.index_price)),
+                cell(format!("{:.2}", latest.data.unrealized_pnl)),
+                cell(format!("{:.4}", latest.data.spread)),
+                cell(format!("{age}s ago")),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+        container(column![header].extend(rows).spacing(6))
+            .padding(10)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    fn view_charts(&self) -> Element<Message> {
+        let controls = row![
+            text("Charts").size(16),
+            horizontal_space(),
+            pick_list(
+                &sparkline::Field::ALL[..],
+                Some(self.charts.field),
+                Message::ChartFieldSelected,
+            ),
+            pick_list(
+                &sparkline::Window::ALL[..],
+                Some(self.charts.window),
+                Message::ChartWindowSelected,
+            ),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        let charts = self.store.iter().filter_map(|(instrument, history)| {
+            let sparkline = self.charts.sparkline(instrument, history)?;
+
+            Some(
+                row![
+                    text(instrument.to_string()).font(Font::MONOSPACE).width(200),
+                    canvas(sparkline).width(Fill).height(60),
+                ]
+                .spacing(10)
+                .align_y(Center)
+                .into(),
+            )
+        });
+
+        container(column![controls].extend(charts).spacing(8))
+            .padding(10)
+            .style(container::rounded_box)
+            .into()
+    }
+
+    fn view_commands(&self) -> Element<Message> {
+        let draft = &self.command_draft;
+        let instruments: Vec<Instrument> =
+            self.store.iter().map(|(instrument, _)| instrument.clone()).collect();
+
+        let spread = (draft.action == commands::Action::SetSpreadTarget).then(|| {
+            text_input("Spread", &draft.spread).width(120).on_input(|spread| {
+                Message::CommandDraftChanged(commands::Draft { spread, ..draft.clone() })
+            })
+        });
+
+        let composer = row![
+            pick_list(&commands::Action::ALL[..], Some(draft.action), |action| {
+                Message::CommandDraftChanged(commands::Draft { action, ..draft.clone() })
+            }),
+            pick_list(instruments, draft.instrument.clone(), |instrument| {
+                Message::CommandDraftChanged(commands::Draft {
+                    instrument: Some(instrument),
+                    ..draft.clone()
+                })
+            })
+            .placeholder("Instrument"),
+        ]
+        .push_maybe(spread)
+        .push(
+            button("Send...").on_press_maybe(
+                draft
+                    .command()
+                    .filter(|_| self.commands.is_some())
+                    .map(Message::ConfirmCommand),
+            ),
+        )
+        .spacing(10)
+        .align_y(Center);
+
+        let status = match (&self.commands, &self.command_status) {
+            (Some(_), _) => text("Connected").style(text::success),
+            (None, Some(reason)) => text(format!("Disconnected: {reason}")).style(text::danger),
+            (None, None) if self.replay.is_some() => {
+                text("Unavailable while replaying").style(text::secondary)
+            }
+            (None, None) => text("Connecting").style(text::secondary),
+        };
+
+        let requests = column(self.requests.iter().rev().map(|request| {
+            let age = self.now.saturating_duration_since(request.sent).as_secs();
+            let (reply, style): (String, fn(&Theme) -> text::Style) = match &request.reply {
+                None => ("waiting for reply".to_string(), text::secondary),
+                Some(Ok(())) => ("done".to_string(), text::success),
+                Some(Err(error)) => (format!("failed: {error}"), text::danger),
+            };
+
+            text(format!("{age}s ago  {}  {reply}", request.command))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(style)
+                .into()
+        }))
+        .spacing(4);
+
+        container(
+            column![
+                row![text("Commands").size(16), horizontal_space(), status.size(14)]
+                    .align_y(Center),
+                composer,
+                requests,
+            ]
+            .spacing(8),
+        )
+        .padding(10)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    fn view_alerts(&self) -> Element<Message> {
+        let alerts = column(self.alerts.iter().map(|alert| {
+            let age = self.feed_now().saturating_duration_since(alert.raised).as_secs();
+            let rule = self
+                .alerts
+                .rule(alert.rule)
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let style = if alert.active { text::danger } else { text::secondary };
+
+            row![
+                text(format!("{age}s ago  {}  {:.4}  {rule}", alert.instrument, alert.value))
+                    .size(12)
+                    .font(Font::MONOSPACE)
+                    .style(style),
+                horizontal_space(),
+                button(text("Acknowledge").size(12)).on_press_maybe(
+                    (!alert.acknowledged)
+                        .then(|| Message::AcknowledgeAlert(alert.rule, alert.instrument.clone())),
+                ),
+            ]
+            .align_y(Center)
+            .into()
+        }))
+        .spacing(4);
+
+        let rules = column(self.alerts.rules().map(|rule| {
+            row![
+                text(rule.to_string()).size(12).font(Font::MONOSPACE),
+                horizontal_space(),
+                button(text("Remove").size(12))
+                    .style(button::danger)
+                    .on_press(Message::RemoveAlertRule(rule.id)),
+            ]
+            .align_y(Center)
+            .into()
+        }))
+        .spacing(4);
+
+        let draft = &self.alert_draft;
+        let scopes: Vec<alerts::Scope> = std::iter::once(alerts::Scope(None))
+            .chain(
+                self.store
+                    .iter()
+                    .map(|(instrument, _)| alerts::Scope(Some(instrument.clone()))),
+            )
+            .collect();
+
+        let editor = row![
+            pick_list(&sparkline::Field::ALL[..], Some(draft.field), |field| {
+                Message::AlertDraftChanged(alerts::Draft { field, ..draft.clone() })
+            }),
+            pick_list(scopes, Some(draft.scope.clone()), |scope| {
+                Message::AlertDraftChanged(alerts::Draft { scope, ..draft.clone() })
+            }),
+            pick_list(&alerts::Comparison::ALL[..], Some(draft.comparison), |comparison| {
+                Message::AlertDraftChanged(alerts::Draft { comparison, ..draft.clone() })
+            }),
+            text_input("Threshold", &draft.threshold).width(120).on_input(|threshold| {
+                Message::AlertDraftChanged(alerts::Draft { threshold, ..draft.clone() })
+            }),
+            text_input("Hysteresis", &draft.hysteresis).width(120).on_input(|hysteresis| {
+                Message::AlertDraftChanged(alerts::Draft { hysteresis, ..draft.clone() })
+            }),
+            button("Add rule").on_press_maybe(draft.rule().map(Message::AddAlertRule)),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        container(
+            column![
+                row![
+                    text("Alerts").size(16),
+                    horizontal_space(),
+                    button("Acknowledge all").on_press_maybe(
+                        (self.alerts.unacknowledged() > 0).then_some(Message::AcknowledgeAllAlerts),
+                    ),
+                ]
+                .align_y(Center),
+                scrollable(alerts).height(120),
+                text("Rules").size(14),
+                rules,
+                editor,
+            ]
+            .spacing(8),
+        )
+        .padding(10)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    fn view_diagnostics(&self) -> Element<Message> {
+        let entries = column(self.diagnostics.iter().map(|diagnostic| {
+            let age = self.now.saturating_duration_since(diagnostic.time).as_secs();
+            let style = match diagnostic.severity {
+                stream_data::Severity::Warning => text::secondary,
+                stream_data::Severity::Error => text::danger,
+            };
+
+            text(format!("{age}s ago  {diagnostic}"))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(style)
+                .into()
+        }))
+        .spacing(4);
+
+        container(
+            column![
+                row![
+                    text("Diagnostics").size(16),
+                    horizontal_space(),
+                    button("Clear").on_press(Message::ClearDiagnostics),
+                ]
+                .align_y(Center),
+                scrollable(entries).height(150),
+            ]
+            .spacing(8),
+        )
+        .padding(10)
+        .style(container::rounded_box)
+        .into()
+    }
+
     fn theme(&self) -> Theme {
         Theme::TokyoNight
     }
 }
 
+fn view_confirmation<'a>(command: &Command) -> Element<'a, Message> {
+    container(
+        column![
+            text("Send this command?").size(16),
+            text(command.to_string()).font(Font::MONOSPACE),
+            row![
+                horizontal_space(),
+                button("Cancel").style(button::secondary).on_press(Message::CancelCommand),
+                button("Send").style(button::danger).on_press(Message::SendCommand),
+            ]
+            .spacing(10),
+        ]
+        .spacing(12),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// Shows `content` in a dialog over `base`, sending `on_blur` when clicking outside of it.
+fn modal<'a>(
+    base: impl Into<Element<'a, Message>>,
+    content: impl Into<Element<'a, Message>>,
+    on_blur: Message,
+) -> Element<'a, Message> {
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(content)).style(|_theme| container::Style {
+                background: Some(
+                    Color {
+                        a: 0.8,
+                        ..Color::BLACK
+                    }
+                    .into(),
+                ),
+                ..container::Style::default()
+            }))
+            .on_press(on_blur)
+        )
+    ]
+    .into()
+}
+
+/// Returns the replay to show in the header, if the feed is a recording.
+fn replay_state() -> Option<replay::State> {
+    match replay::get() {
+        Source::Live { .. } => None,
+        Source::Replay { path, speed } => Some(replay::State::new(path.clone(), *speed)),
+    }
+}
+
 fn square<'a>(size: impl Into<Length> + Copy) -> Element<'a, Message> {
     struct Square;
 
@@ -691,6 +1372,17 @@ mod style {
         }
     }
 
+    pub fn item_alert(theme: &Theme) -> container::Style {
+        container::Style {
+            border: iced::Border {
+                color: theme.extended_palette().danger.strong.color.into(),
+                width: 2.0,
+                radius: 5.0.into(),
+            },
+            ..Default::default()
+        }
+    }
+
     pub fn item_dragged(theme: &Theme) -> container::Style {
         container::Style {
             border: iced::Border {
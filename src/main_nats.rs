@@ -1,63 +1,724 @@
-use std::sync::{Arc, Mutex};
+use std::fmt;
+use std::time::Duration;
 
-use iced::widget::{button, column, container, text, Button, Column, Container, Text};
-use iced::{alignment, Center, Color, Element, Length};
+use async_nats::jetstream::{AckKind, Context};
+use iced::widget::{
+    button, center, column, container, horizontal_space, mouse_area, opaque, pick_list, row,
+    scrollable, stack, text, text_input,
+};
+use iced::{Center, Color, Element, Font, Task};
 use iced::Length::Fill;
+use time::OffsetDateTime;
 mod nats;
 
+use nats::nats::{ConsumerSummary, Delivered, Purge, Selection, StoredMessage, StreamSummary};
+
+/// How many messages a browse or fetch asks for unless changed.
+const DEFAULT_BATCH_SIZE: usize = 10;
+
 pub fn main() -> iced::Result {
-    iced::application("NATS Message", Nats::update, Nats::view)
-    .run()
+    if let Err(error) = nats::config::init() {
+        eprintln!("Invalid NATS configuration: {error}");
+        std::process::exit(1);
+    }
+
+    iced::application("JetStream Inspector", Nats::update, Nats::view)
+    .run_with(Nats::new)
 }
 
-#[derive(Default)]
+/// A JetStream inspector: streams and their consumers, stored messages, and purging.
 struct Nats {
-    message: Arc<Mutex<Vec<String>>>,
+    context: Option<Context>,
+    status: Status,
+    streams: Vec<StreamSummary>,
+    stream: Option<String>,
+    consumers: Vec<ConsumerSummary>,
+    consumer: Option<String>,
+    mode: Mode,
+    first: String,
+    last: String,
+    minutes: String,
+    batch_size: String,
+    stored: Vec<StoredMessage>,
+    delivered: Vec<Delivered>,
+    purge_subject: String,
+    purge_before: String,
+    /// The removal waiting for confirmation, if any.
+    confirming: Option<Removal>,
+}
+
+/// The outcome of the latest request.
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Idle,
+    Loading(&'static str),
+    Done(String),
+    Failed(String),
+}
+
+/// How stored messages are selected for browsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Sequences,
+    Since,
+}
+
+impl Mode {
+    const ALL: [Mode; 2] = [Mode::Sequences, Mode::Since];
 }
 
-#[derive(Debug, Clone, Copy)]
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Mode::Sequences => "By sequence",
+            Mode::Since => "By time",
+        })
+    }
+}
+
+/// An irreversible change of the stored messages, sent only once confirmed.
+#[derive(Debug, Clone, PartialEq)]
+enum Removal {
+    Delete(u64),
+    Purge(Purge),
+}
+
+impl fmt::Display for Removal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Removal::Delete(sequence) => write!(f, "Delete message {sequence}"),
+            Removal::Purge(Purge::All) => write!(f, "Purge every message"),
+            Removal::Purge(Purge::Subject(subject)) => {
+                write!(f, "Purge every message on {subject}")
+            }
+            Removal::Purge(Purge::Before(sequence)) => {
+                write!(f, "Purge every message before sequence {sequence}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 enum Message {
-    OnMessage,
+    Connected(Result<Context, String>),
+    RefreshStreams,
+    StreamsLoaded(Result<Vec<StreamSummary>, String>),
+    SelectStream(String),
+    ConsumersLoaded(Result<Vec<ConsumerSummary>, String>),
+    SelectConsumer(String),
+    ModeSelected(Mode),
+    FirstChanged(String),
+    LastChanged(String),
+    MinutesChanged(String),
+    BatchSizeChanged(String),
+    Browse,
+    Browsed(Result<Vec<StoredMessage>, String>),
+    Fetch,
+    Fetched(Result<Vec<Delivered>, String>),
+    Ack(u64, AckKind),
+    Acked(u64, Result<(), String>),
+    Confirm(Removal),
+    CancelRemoval,
+    Remove,
+    Deleted(u64, Result<(), String>),
+    PurgeSubjectChanged(String),
+    PurgeBeforeChanged(String),
+    Purged(Result<u64, String>),
 }
 
 impl Nats {
-    fn update(&mut self, message: Message) {
+    fn new() -> (Self, Task<Message>) {
+        (
+            Self {
+                context: None,
+                status: Status::Loading("Connecting"),
+                streams: Vec::new(),
+                stream: None,
+                consumers: Vec::new(),
+                consumer: None,
+                mode: Mode::Sequences,
+                first: "1".to_string(),
+                last: String::new(),
+                minutes: "5".to_string(),
+                batch_size: DEFAULT_BATCH_SIZE.to_string(),
+                stored: Vec::new(),
+                delivered: Vec::new(),
+                purge_subject: String::new(),
+                purge_before: String::new(),
+                confirming: None,
+            },
+            Task::perform(nats::nats::connect(), |result| {
+                Message::Connected(result.map_err(|error| error.to_string()))
+            }),
+        )
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::OnMessage => {
-                let message_clone = Arc::clone(&self.message);
-                std::thread::spawn(move || {
-                    // Ideally, use an existing runtime if possible
-                    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
-                    
-                    match runtime.block_on(nats::nats::get_nats_message()) {
-                        Ok(messages) => {
-                            let mut messages_lock = message_clone.lock().unwrap();
-                            *messages_lock = messages;
-                        }
-                        Err(e) => eprintln!("Error fetching NATS message: {:?}", e),
+            Message::Connected(Ok(context)) => {
+                self.context = Some(context);
+                self.status = Status::Idle;
+
+                self.refresh_streams()
+            }
+            Message::RefreshStreams => self.refresh_streams(),
+            Message::StreamsLoaded(result) => {
+                if let Some(streams) = self.finish(result) {
+                    self.streams = streams;
+                }
+
+                Task::none()
+            }
+            Message::SelectStream(stream) => {
+                self.stream = Some(stream);
+                self.consumer = None;
+                self.consumers.clear();
+                self.stored.clear();
+                self.delivered.clear();
+
+                self.refresh_consumers()
+            }
+            Message::ConsumersLoaded(result) => {
+                if let Some(consumers) = self.finish(result) {
+                    self.consumers = consumers;
+                }
+
+                Task::none()
+            }
+            Message::SelectConsumer(consumer) => {
+                self.consumer = Some(consumer);
+                self.delivered.clear();
+
+                Task::none()
+            }
+            Message::ModeSelected(mode) => {
+                self.mode = mode;
+
+                Task::none()
+            }
+            Message::FirstChanged(first) => {
+                self.first = first;
+
+                Task::none()
+            }
+            Message::LastChanged(last) => {
+                self.last = last;
+
+                Task::none()
+            }
+            Message::MinutesChanged(minutes) => {
+                self.minutes = minutes;
+
+                Task::none()
+            }
+            Message::BatchSizeChanged(batch_size) => {
+                self.batch_size = batch_size;
+
+                Task::none()
+            }
+            Message::Browse => {
+                let (Some(context), Some(stream), Some(selection), Some(batch_size)) = (
+                    self.context.clone(),
+                    self.stream.clone(),
+                    self.selection(),
+                    self.batch_size(),
+                ) else {
+                    return Task::none();
+                };
+                self.status = Status::Loading("Browsing");
+
+                Task::perform(
+                    nats::nats::browse(context, stream, selection, batch_size),
+                    |result| Message::Browsed(result.map_err(|error| error.to_string())),
+                )
+            }
+            Message::Browsed(result) => {
+                if let Some(stored) = self.finish(result) {
+                    self.done("Browsing", format!("{} messages", stored.len()));
+                    self.stored = stored;
+                }
+
+                Task::none()
+            }
+            Message::Fetch => {
+                let (Some(context), Some(stream), Some(consumer), Some(batch_size)) = (
+                    self.context.clone(),
+                    self.stream.clone(),
+                    self.consumer.clone(),
+                    self.batch_size(),
+                ) else {
+                    return Task::none();
+                };
+                self.status = Status::Loading("Fetching");
+
+                Task::perform(
+                    nats::nats::fetch(context, stream, consumer, batch_size),
+                    |result| Message::Fetched(result.map_err(|error| error.to_string())),
+                )
+            }
+            Message::Fetched(result) => {
+                if let Some(delivered) = self.finish(result) {
+                    self.done("Fetching", format!("{} messages to acknowledge", delivered.len()));
+                    self.delivered.extend(delivered);
+                }
+
+                Task::none()
+            }
+            Message::Ack(sequence, kind) => {
+                // The message stays listed until the server took the ack, so a failed one can be
+                // sent again.
+                let Some(delivered) = self
+                    .delivered
+                    .iter()
+                    .find(|delivered| delivered.message.sequence == sequence)
+                    .cloned()
+                else {
+                    return Task::none();
+                };
+                self.status = Status::Loading("Acknowledging");
+
+                Task::perform(nats::nats::ack(delivered, kind), move |result| {
+                    Message::Acked(sequence, result.map_err(|error| error.to_string()))
+                })
+            }
+            Message::Acked(sequence, result) => {
+                if self.finish(result).is_some() {
+                    self.done("Acknowledging", format!("Acknowledged message {sequence}"));
+                    self.delivered.retain(|delivered| delivered.message.sequence != sequence);
+                }
+
+                self.refresh_consumers()
+            }
+            Message::Confirm(removal) => {
+                self.confirming = Some(removal);
+
+                Task::none()
+            }
+            Message::CancelRemoval => {
+                self.confirming = None;
+
+                Task::none()
+            }
+            Message::Remove => {
+                let (Some(context), Some(stream), Some(removal)) =
+                    (self.context.clone(), self.stream.clone(), self.confirming.take())
+                else {
+                    return Task::none();
+                };
+
+                match removal {
+                    Removal::Delete(sequence) => {
+                        self.status = Status::Loading("Deleting");
+
+                        Task::perform(nats::nats::delete(context, stream, sequence), move |result| {
+                            Message::Deleted(sequence, result.map_err(|error| error.to_string()))
+                        })
+                    }
+                    Removal::Purge(purge) => {
+                        self.status = Status::Loading("Purging");
+
+                        Task::perform(nats::nats::purge(context, stream, purge), |result| {
+                            Message::Purged(result.map_err(|error| error.to_string()))
+                        })
                     }
-                });
+                }
+            }
+            Message::Deleted(sequence, result) => {
+                if self.finish(result).is_some() {
+                    self.done("Deleting", format!("Deleted message {sequence}"));
+                    self.stored.retain(|message| message.sequence != sequence);
+                }
+
+                self.refresh_streams()
+            }
+            Message::PurgeSubjectChanged(subject) => {
+                self.purge_subject = subject;
+
+                Task::none()
+            }
+            Message::PurgeBeforeChanged(sequence) => {
+                self.purge_before = sequence;
+
+                Task::none()
+            }
+            Message::Purged(result) => {
+                if let Some(purged) = self.finish(result) {
+                    self.done("Purging", format!("Purged {purged} messages"));
+                    self.stored.clear();
+                }
+
+                self.refresh_streams()
+            }
+            Message::Connected(Err(error)) => {
+                eprintln!("Error connecting to NATS: {error}");
+                self.status = Status::Failed(error);
+
+                Task::none()
+            }
+        }
+    }
+
+    /// Returns the value of a successful request, or shows the error of a failed one.
+    fn finish<T>(&mut self, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                eprintln!("JetStream request failed: {error}");
+                self.status = Status::Failed(error);
+                None
+            }
+        }
+    }
+
+    /// Shows the outcome of the `request` that set the loading status, unless a later request
+    /// is still running.
+    fn done(&mut self, request: &'static str, outcome: String) {
+        if matches!(self.status, Status::Loading(loading) if loading != request) {
+            return;
+        }
+
+        self.status = Status::Done(outcome);
+    }
+
+    fn refresh_streams(&self) -> Task<Message> {
+        let Some(context) = self.context.clone() else {
+            return Task::none();
+        };
+
+        Task::batch([
+            Task::perform(nats::nats::list_streams(context), |result| {
+                Message::StreamsLoaded(result.map_err(|error| error.to_string()))
+            }),
+            self.refresh_consumers(),
+        ])
+    }
+
+    fn refresh_consumers(&self) -> Task<Message> {
+        let (Some(context), Some(stream)) = (self.context.clone(), self.stream.clone()) else {
+            return Task::none();
+        };
+
+        Task::perform(nats::nats::list_consumers(context, stream), |result| {
+            Message::ConsumersLoaded(result.map_err(|error| error.to_string()))
+        })
+    }
+
+    /// Returns the typed batch size, if it is a positive number.
+    fn batch_size(&self) -> Option<usize> {
+        self.batch_size.trim().parse().ok().filter(|size| *size > 0)
+    }
+
+    /// Returns the typed selection of messages to browse, if it is valid.
+    ///
+    /// Sequences start at 1, an empty last sequence browses up to the end of the stream, and
+    /// the time must be representable.
+    fn selection(&self) -> Option<Selection> {
+        match self.mode {
+            Mode::Sequences => {
+                let first = self.first.trim().parse().ok().filter(|first| *first > 0)?;
+                let last = match self.last.trim() {
+                    "" => u64::MAX,
+                    last => last.parse().ok().filter(|last| *last >= first)?,
+                };
+
+                Some(Selection::Sequences { first, last })
+            }
+            Mode::Since => {
+                let minutes: u64 = self.minutes.trim().parse().ok()?;
+                let ago = time::Duration::try_from(Duration::from_secs(minutes.checked_mul(60)?))
+                    .ok()?;
+
+                Some(Selection::Since(OffsetDateTime::now_utc().checked_sub(ago)?))
             }
         }
     }
 
     fn view(&self) -> Element<Message> {
-        let message = self.message.lock().unwrap();
-    
-        // Join the Vec<String> into a single String.
-        let message_text = message.iter().cloned().collect::<Vec<_>>()
-            .join(" ");  // Adjust the separator as needed, here it's a space.
-        container(
+        let status = match &self.status {
+            Status::Idle => text(""),
+            Status::Loading(action) => text(format!("{action}...")),
+            Status::Done(result) => text(result).style(text::success),
+            Status::Failed(error) => text(format!("Error: {error}")).style(text::danger),
+        };
+
+        let header = row![
+            text("JetStream Inspector").size(20),
+            horizontal_space(),
+            status,
+            button("Refresh")
+                .on_press_maybe(self.context.is_some().then_some(Message::RefreshStreams)),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        let streams = column(self.streams.iter().map(|stream| {
+            let selected = self.stream.as_ref() == Some(&stream.name);
+
+            button(
+                column![
+                    text(&stream.name).size(16),
+                    text(format!("{} messages, {} bytes", stream.messages, stream.bytes)).size(12),
+                    text(format!(
+                        "sequences {}..={}, {} consumers",
+                        stream.first_sequence, stream.last_sequence, stream.consumers
+                    ))
+                    .size(12),
+                    text(stream.subjects.join(", ")).size(12),
+                ]
+                .spacing(2),
+            )
+            .width(Fill)
+            .style(if selected { button::primary } else { button::secondary })
+            .on_press(Message::SelectStream(stream.name.clone()))
+            .into()
+        }))
+        .spacing(6);
+
+        let detail: Element<Message> = if self.stream.is_some() {
+            column![self.view_consumers(), self.view_browser(), self.view_purge()]
+                .spacing(20)
+                .into()
+        } else {
+            text("Select a stream").into()
+        };
+
+        let inspector = container(
             column![
-                button("Call Nats").on_press(Message::OnMessage),
-                text(message_text).size(50),
+                header,
+                row![
+                    scrollable(streams).width(300).height(Fill),
+                    scrollable(detail).width(Fill).height(Fill),
+                ]
+                .spacing(20),
             ]
-            .spacing(10)
+            .spacing(10),
         )
-        .padding(10)
-        .center_x(Fill)
-        .center_y(Fill)
-        .align_x(Center)
+        .padding(10);
+
+        match (&self.confirming, &self.stream) {
+            (Some(removal), Some(stream)) => {
+                modal(inspector, view_confirmation(removal, stream), Message::CancelRemoval)
+            }
+            _ => inspector.into(),
+        }
+    }
+
+    fn view_consumers(&self) -> Element<Message> {
+        let consumers = column(self.consumers.iter().map(|consumer| {
+            let selected = self.consumer.as_ref() == Some(&consumer.name);
+            let kind = if consumer.durable { "durable" } else { "ephemeral" };
+
+            button(
+                text(format!(
+                    "{} ({kind}): {} pending, {} awaiting ack, delivered {}, ack floor {}",
+                    consumer.name,
+                    consumer.pending,
+                    consumer.ack_pending,
+                    consumer.delivered_sequence,
+                    consumer.ack_floor_sequence,
+                ))
+                .size(12),
+            )
+            .width(Fill)
+            .style(if selected { button::primary } else { button::secondary })
+            .on_press(Message::SelectConsumer(consumer.name.clone()))
+            .into()
+        }))
+        .spacing(4);
+
+        let fetch = self.consumer.as_ref().map(|consumer| {
+            row![
+                text(format!("Messages taken from {consumer} wait here to be acknowledged"))
+                    .size(12),
+                horizontal_space(),
+                button("Fetch").on_press_maybe(self.batch_size().map(|_| Message::Fetch)),
+            ]
+            .spacing(10)
+            .align_y(Center)
+        });
+
+        let delivered = column(self.delivered.iter().map(|delivered| {
+            let sequence = delivered.message.sequence;
+
+            column![
+                view_message(&delivered.message),
+                row![
+                    button(text("Ack").size(12)).on_press(Message::Ack(sequence, AckKind::Ack)),
+                    button(text("Nak").size(12))
+                        .style(button::secondary)
+                        .on_press(Message::Ack(sequence, AckKind::Nak(None))),
+                    button(text("Term").size(12))
+                        .style(button::danger)
+                        .on_press(Message::Ack(sequence, AckKind::Term)),
+                ]
+                .spacing(6),
+            ]
+            .spacing(4)
+            .into()
+        }))
+        .spacing(10);
+
+        column![text("Consumers").size(18), consumers]
+            .push_maybe(fetch)
+            .push(delivered)
+            .spacing(8)
+            .into()
+    }
+
+    fn view_browser(&self) -> Element<Message> {
+        let selection: Element<Message> = match self.mode {
+            Mode::Sequences => row![
+                text_input("First sequence", &self.first)
+                    .on_input(Message::FirstChanged)
+                    .width(140),
+                text_input("Last sequence", &self.last)
+                    .on_input(Message::LastChanged)
+                    .width(140),
+            ]
+            .spacing(10)
+            .into(),
+            Mode::Since => row![
+                text_input("Minutes", &self.minutes)
+                    .on_input(Message::MinutesChanged)
+                    .width(100),
+                text("minutes ago"),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into(),
+        };
+
+        let can_browse = self.selection().is_some() && self.batch_size().is_some();
+
+        let controls = row![
+            pick_list(&Mode::ALL[..], Some(self.mode), Message::ModeSelected),
+            selection,
+            text("Batch size"),
+            text_input("Batch size", &self.batch_size)
+                .on_input(Message::BatchSizeChanged)
+                .width(80),
+            button("Browse").on_press_maybe(can_browse.then_some(Message::Browse)),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        let stored = column(self.stored.iter().map(|message| {
+            column![
+                view_message(message),
+                button(text("Delete").size(12))
+                    .style(button::danger)
+                    .on_press(Message::Confirm(Removal::Delete(message.sequence))),
+            ]
+            .spacing(4)
+            .into()
+        }))
+        .spacing(10);
+
+        column![text("Messages").size(18), controls, stored].spacing(8).into()
+    }
+
+    fn view_purge(&self) -> Element<Message> {
+        // The server takes a sequence of 0 as unset and would purge everything.
+        let before = self
+            .purge_before
+            .trim()
+            .parse()
+            .ok()
+            .filter(|sequence| *sequence > 0)
+            .map(Purge::Before);
+        let subject = (!self.purge_subject.trim().is_empty())
+            .then(|| Purge::Subject(self.purge_subject.trim().to_string()));
+
+        column![
+            text("Purge").size(18),
+            row![
+                text_input("Subject", &self.purge_subject)
+                    .on_input(Message::PurgeSubjectChanged)
+                    .width(240),
+                button("Purge subject")
+                    .style(button::danger)
+                    .on_press_maybe(subject.map(|purge| Message::Confirm(Removal::Purge(purge)))),
+            ]
+            .spacing(10),
+            row![
+                text_input("Sequence", &self.purge_before)
+                    .on_input(Message::PurgeBeforeChanged)
+                    .width(240),
+                button("Purge before sequence")
+                    .style(button::danger)
+                    .on_press_maybe(before.map(|purge| Message::Confirm(Removal::Purge(purge)))),
+            ]
+            .spacing(10),
+            button("Purge everything")
+                .style(button::danger)
+                .on_press(Message::Confirm(Removal::Purge(Purge::All))),
+        ]
+        .spacing(8)
         .into()
     }
-}
\ No newline at end of file
+}
+
+fn view_message(message: &StoredMessage) -> Element<Message> {
+    let headers = column(message.headers.iter().map(|(name, value)| {
+        text(format!("{name}: {value}")).size(12).font(Font::MONOSPACE).into()
+    }));
+
+    container(
+        column![
+            text(format!("#{}  {}  {}", message.sequence, message.subject, message.published))
+                .size(12),
+            headers,
+            text(&message.payload).size(12).font(Font::MONOSPACE),
+        ]
+        .spacing(4),
+    )
+    .padding(8)
+    .width(Fill)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn view_confirmation<'a>(removal: &Removal, stream: &str) -> Element<'a, Message> {
+    container(
+        column![
+            text("This cannot be undone").size(16),
+            text(format!("{removal} of {stream}?")),
+            row![
+                horizontal_space(),
+                button("Cancel").style(button::secondary).on_press(Message::CancelRemoval),
+                button("Remove").style(button::danger).on_press(Message::Remove),
+            ]
+            .spacing(10),
+        ]
+        .spacing(12),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// Shows `content` in a dialog over `base`, sending `on_blur` when clicking outside of it.
+fn modal<'a>(
+    base: impl Into<Element<'a, Message>>,
+    content: impl Into<Element<'a, Message>>,
+    on_blur: Message,
+) -> Element<'a, Message> {
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(content)).style(|_theme| container::Style {
+                background: Some(
+                    Color {
+                        a: 0.8,
+                        ..Color::BLACK
+                    }
+                    .into(),
+                ),
+                ..container::Style::default()
+            }))
+            .on_press(on_blur)
+        )
+    ]
+    .into()
+}
@@ -0,0 +1,164 @@
+use iced::widget::{column, row, text};
+use iced::{Element, Fill, Font};
+
+use crate::sparkline::Field;
+use crate::store::Store;
+use crate::Message;
+
+/// A dashboard module rendering the latest [`StreamData`] of every instrument.
+///
+/// [`StreamData`]: crate::stream_data::StreamData
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Module {
+    /// Positions, exposure and entry prices.
+    Risk,
+    /// Realized and unrealized PnL.
+    Performance,
+    /// Maker and taker volumes.
+    Volume,
+}
+
+impl Module {
+    pub fn title(self) -> &'static str {
+        match self {
+            Module::Risk => "Perp Risk Metrics Module",
+            Module::Performance => "Perp Performance Metrics Module",
+            Module::Volume => "Perp Volume Module",
+        }
+    }
+
+    /// The fields whose alerts flash this module.
+    pub fn fields(self) -> &'static [Field] {
+        match self {
+            Module::Risk => &[Field::Spread, Field::IndexPrice, Field::NetNotional],
+            Module::Performance => &[Field::UnrealizedPnl],
+            Module::Volume => &[],
+        }
+    }
+
+    pub fn view(self, store: &Store) -> Element<'_, Message> {
+        let content = if store.is_empty() {
+            text("Waiting for market data...").size(14).into()
+        } else {
+            match self {
+                Module::Risk => risk(store),
+                Module::Performance => performance(store),
+                Module::Volume => volume(store),
+            }
+        };
+
+        column![text(self.title()).size(16), content].spacing(8).into()
+    }
+}
+
+fn risk(store: &Store) -> Element<'_, Message> {
+    table(
+        &[
+            "Instrument",
+            "Long size",
+            "Long notional",
+            "Short size",
+            "Short notional",
+            "Net exposure",
+            "Spread",
+            "Long entry",
+            "Short entry",
+        ],
+        store.iter().map(|(instrument, history)| {
+            let data = &history.latest().data;
+
+            vec![
+                cell(instrument.to_string()),
+                cell(format!("{:.4}", data.long_size)),
+                cell(usd(data.long_usd_notional)),
+                cell(format!("{:.4}", data.short_size)),
+                cell(usd(data.short_usd_notional)),
+                signed(data.long_usd_notional - data.short_usd_notional, usd),
+                cell(format!("{:.4}", data.spread)),
+                cell(entry(data.long_ave_entry_price, data.index_price)),
+                cell(entry(data.short_ave_entry_price, data.index_price)),
+            ]
+        }),
+    )
+}
+
+fn performance(store: &Store) -> Element<'_, Message> {
+    table(
+        &["Instrument", "DTD PnL", "WTD PnL", "MTD PnL", "Unrealized PnL"],
+        store.iter().map(|(instrument, history)| {
+            let data = &history.latest().data;
+
+            vec![
+                cell(instrument.to_string()),
+                signed(data.dtd_pnl, usd),
+                signed(data.wtd_pnl, usd),
+                signed(data.mtd_pnl, usd),
+                signed(data.unrealized_pnl, usd),
+            ]
+        }),
+    )
+}
+
+fn volume(store: &Store) -> Element<'_, Message> {
+    let period = |maker: f64, taker: f64| {
+        let total = maker + taker;
+        let maker_share = if total > 0.0 { maker / total * 100.0 } else { 0.0 };
+
+        cell(format!("{} / {} ({maker_share:.0}% maker)", usd(maker), usd(taker)))
+    };
+
+    table(
+        &["Instrument", "DTD maker / taker", "WTD maker / taker", "MTD maker / taker"],
+        store.iter().map(move |(instrument, history)| {
+            let data = &history.latest().data;
+
+            vec![
+                cell(instrument.to_string()),
+                period(data.dtd_volume_maker, data.dtd_volume_taker),
+                period(data.wtd_volume_maker, data.wtd_volume_taker),
+                period(data.mtd_volume_maker, data.mtd_volume_taker),
+            ]
+        }),
+    )
+}
+
+fn table<'a>(
+    header: &[&'static str],
+    rows: impl Iterator<Item = Vec<Element<'a, Message>>>,
+) -> Element<'a, Message> {
+    let header = row(header.iter().map(|title| text(*title).size(12).width(Fill).into())).spacing(10);
+
+    column![header]
+        .extend(rows.map(|cells| row(cells).spacing(10).into()))
+        .spacing(4)
+        .into()
+}
+
+fn cell<'a>(content: String) -> Element<'a, Message> {
+    text(content).size(14).font(Font::MONOSPACE).width(Fill).into()
+}
+
+/// A value colored by its sign.
+fn signed<'a>(value: f64, format: fn(f64) -> String) -> Element<'a, Message> {
+    let style = if value < 0.0 { text::danger } else { text::success };
+
+    text(format(value))
+        .size(14)
+        .font(Font::MONOSPACE)
+        .width(Fill)
+        .style(style)
+        .into()
+}
+
+fn usd(value: f64) -> String {
+    format!("${value:.2}")
+}
+
+/// An entry price and its distance from the index price.
+fn entry(price: f64, index_price: f64) -> String {
+    if price == 0.0 || index_price == 0.0 {
+        return format!("{price:.2}");
+    }
+
+    format!("{price:.2} ({:+.2}%)", (index_price / price - 1.0) * 100.0)
+}
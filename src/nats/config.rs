@@ -0,0 +1,341 @@
+//! The NATS connection settings shared by the NATS apps.
+//!
+//! Settings are read once from a JSON file, `nats.json` in the working directory or the file
+//! named by `NATS_CONFIG` (see `nats.example.json`), and then overridden by environment variables:
+//!
+//! | Variable | Setting |
+//! |---|---|
+//! | `NATS_URL` | Comma-separated server URLs |
+//! | `NATS_TOKEN` | Token authentication |
+//! | `NATS_USER`, `NATS_PASSWORD` | User and password authentication |
+//! | `NATS_NKEY` | NKey seed authentication |
+//! | `NATS_CREDS` | Credentials file authentication |
+//! | `NATS_TLS_REQUIRED` | `true` or `false` |
+//! | `NATS_TLS_CA` | Comma-separated root certificate files |
+//! | `NATS_TLS_CERT`, `NATS_TLS_KEY` | Client certificate and key files |
+//! | `NATS_STREAM` | JetStream stream name |
+//! | `NATS_SUBJECT` | Subject filter of the consumer |
+//! | `NATS_CONSUMER` | Durable consumer name |
+//! | `NATS_DELIVER_POLICY` | `all`, `last`, `new`, `last_per_subject` or `by_start_sequence:<n>` |
+//! | `NATS_COMMAND_SUBJECT` | Subject prefix of the dashboard commands |
+//! | `NATS_REQUEST_TIMEOUT_MS` | How long to wait for the reply to a command |
+//!
+//! The authentication variables replace the `auth` of the file, and at most one of
+//! `NATS_TOKEN`, `NATS_USER`, `NATS_NKEY` and `NATS_CREDS` may be set.
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use async_nats::jetstream::consumer::{self, pull};
+use async_nats::{Client, ConnectOptions, ServerAddr};
+use serde::Deserialize;
+
+const DEFAULT_PATH: &str = "nats.json";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Loads the [`Config`] of the app. Call this once at startup, before any connection is made.
+pub fn init() -> Result<&'static Config, Error> {
+    let config = Config::load()?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// Returns the [`Config`] loaded by [`init`].
+///
+/// # Panics
+/// Panics if [`init`] was not called, so an invalid configuration is always reported at
+/// startup rather than by the first connection.
+pub fn get() -> &'static Config {
+    CONFIG
+        .get()
+        .expect("nats::config::init must be called before connecting")
+}
+
+/// The NATS connection settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The server URLs to connect to.
+    pub servers: Vec<String>,
+    pub auth: Auth,
+    pub tls: Tls,
+    /// The JetStream stream to consume.
+    pub stream: String,
+    /// The subject filter of the consumer. Every subject of the stream if `None`.
+    pub subject: Option<String>,
+    /// The name of the durable consumer.
+    pub consumer: String,
+    pub deliver_policy: DeliverPolicy,
+    /// The subject prefix of the commands sent by the dashboard, followed by the command name.
+    pub command_subject: String,
+    /// How long to wait for the reply to a command, in milliseconds.
+    pub request_timeout_ms: u64,
+}
+
+/// How to authenticate with the servers.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    #[default]
+    None,
+    Token(String),
+    UserPassword { user: String, password: String },
+    /// An NKey seed.
+    Nkey(String),
+    /// A `.creds` file with a JWT and NKey seed.
+    Credentials(PathBuf),
+}
+
+/// The TLS settings of the connection.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tls {
+    /// Whether to refuse connecting without TLS.
+    pub required: bool,
+    /// Additional root certificates to trust, in PEM format.
+    pub root_certificates: Vec<PathBuf>,
+    /// The client certificate and key for mutual TLS, in PEM format.
+    pub client_certificate: Option<(PathBuf, PathBuf)>,
+}
+
+/// Which messages of the stream a new consumer starts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliverPolicy {
+    #[default]
+    All,
+    Last,
+    New,
+    LastPerSubject,
+    ByStartSequence(u64),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            servers: vec!["nats://127.0.0.1:4222".to_string()],
+            auth: Auth::None,
+            tls: Tls::default(),
+            stream: "mm_perp_summary_derive".to_string(),
+            subject: None,
+            consumer: "consumer".to_string(),
+            deliver_policy: DeliverPolicy::All,
+            command_subject: "mm_perp_command".to_string(),
+            request_timeout_ms: 5_000,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration file, if any, and applies the environment overrides.
+    pub fn load() -> Result<Self, Error> {
+        let mut config = match env::var_os("NATS_CONFIG") {
+            Some(path) => Self::read(Path::new(&path))?,
+            None if Path::new(DEFAULT_PATH).exists() => Self::read(Path::new(DEFAULT_PATH))?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    fn read(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+        serde_json::from_slice(&contents).map_err(|error| Error::Parse(path.to_owned(), error))
+    }
+
+    fn apply_env(&mut self) -> Result<(), Error> {
+        if let Some(servers) = var("NATS_URL") {
+            self.servers = list(&servers).map(str::to_owned).collect();
+        }
+
+        let auth_vars = ["NATS_TOKEN", "NATS_USER", "NATS_NKEY", "NATS_CREDS"];
+        if let [first, second, ..] = auth_vars
+            .into_iter()
+            .filter(|name| var(name).is_some())
+            .collect::<Vec<_>>()[..]
+        {
+            return Err(Error::Conflict(first, second));
+        }
+        if var("NATS_PASSWORD").is_some() && var("NATS_USER").is_none() {
+            return Err(Error::Env("NATS_USER", String::new()));
+        }
+
+        if let Some(token) = var("NATS_TOKEN") {
+            self.auth = Auth::Token(token);
+        }
+        if let Some(user) = var("NATS_USER") {
+            let password = var("NATS_PASSWORD").unwrap_or_default();
+            self.auth = Auth::UserPassword { user, password };
+        }
+        if let Some(seed) = var("NATS_NKEY") {
+            self.auth = Auth::Nkey(seed);
+        }
+        if let Some(path) = var("NATS_CREDS") {
+            self.auth = Auth::Credentials(path.into());
+        }
+
+        if let Some(required) = var("NATS_TLS_REQUIRED") {
+            self.tls.required = required
+                .parse()
+                .map_err(|_| Error::Env("NATS_TLS_REQUIRED", required))?;
+        }
+        if let Some(paths) = var("NATS_TLS_CA") {
+            self.tls.root_certificates = list(&paths).map(PathBuf::from).collect();
+        }
+        match (var("NATS_TLS_CERT"), var("NATS_TLS_KEY")) {
+            (Some(cert), Some(key)) => {
+                self.tls.client_certificate = Some((cert.into(), key.into()));
+            }
+            (None, None) => {}
+            (Some(_), None) => return Err(Error::Env("NATS_TLS_KEY", String::new())),
+            (None, Some(_)) => return Err(Error::Env("NATS_TLS_CERT", String::new())),
+        }
+
+        if let Some(stream) = var("NATS_STREAM") {
+            self.stream = stream;
+        }
+        if let Some(subject) = var("NATS_SUBJECT") {
+            self.subject = Some(subject);
+        }
+        if let Some(consumer) = var("NATS_CONSUMER") {
+            self.consumer = consumer;
+        }
+        if let Some(policy) = var("NATS_DELIVER_POLICY") {
+            self.deliver_policy = policy
+                .parse()
+                .map_err(|_| Error::Env("NATS_DELIVER_POLICY", policy))?;
+        }
+        if let Some(subject) = var("NATS_COMMAND_SUBJECT") {
+            self.command_subject = subject;
+        }
+        if let Some(timeout) = var("NATS_REQUEST_TIMEOUT_MS") {
+            self.request_timeout_ms = timeout
+                .parse()
+                .map_err(|_| Error::Env("NATS_REQUEST_TIMEOUT_MS", timeout))?;
+        }
+
+        Ok(())
+    }
+
+    /// Connects to the servers with the configured authentication and TLS settings.
+    pub async fn connect(&self) -> Result<Client, async_nats::Error> {
+        self.connect_with(|options| options).await
+    }
+
+    /// Connects like [`Config::connect`], letting `configure` add to the options, e.g. an
+    /// event callback.
+    pub async fn connect_with(
+        &self,
+        configure: impl FnOnce(ConnectOptions) -> ConnectOptions,
+    ) -> Result<Client, async_nats::Error> {
+        let mut options = match &self.auth {
+            Auth::None => ConnectOptions::new(),
+            Auth::Token(token) => ConnectOptions::with_token(token.clone()),
+            Auth::UserPassword { user, password } => {
+                ConnectOptions::with_user_and_password(user.clone(), password.clone())
+            }
+            Auth::Nkey(seed) => ConnectOptions::with_nkey(seed.clone()),
+            Auth::Credentials(path) => ConnectOptions::with_credentials_file(path).await?,
+        };
+
+        options = options.require_tls(self.tls.required);
+        for path in &self.tls.root_certificates {
+            options = options.add_root_certificates(path.clone());
+        }
+        if let Some((cert, key)) = &self.tls.client_certificate {
+            options = options.add_client_certificate(cert.clone(), key.clone());
+        }
+
+        options = configure(options);
+
+        let servers = self
+            .servers
+            .iter()
+            .map(|server| server.parse())
+            .collect::<Result<Vec<ServerAddr>, _>>()?;
+
+        Ok(options.connect(servers.as_slice()).await?)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    /// Returns the configuration of the durable pull consumer.
+    pub fn consumer_config(&self) -> pull::Config {
+        pull::Config {
+            durable_name: Some(self.consumer.clone()),
+            filter_subject: self.subject.clone().unwrap_or_default(),
+            deliver_policy: self.deliver_policy.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::str::FromStr for DeliverPolicy {
+    type Err = ();
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "all" => Ok(Self::All),
+            "last" => Ok(Self::Last),
+            "new" => Ok(Self::New),
+            "last_per_subject" => Ok(Self::LastPerSubject),
+            _ => policy
+                .strip_prefix("by_start_sequence:")
+                .and_then(|sequence| sequence.parse().ok())
+                .map(Self::ByStartSequence)
+                .ok_or(()),
+        }
+    }
+}
+
+impl From<DeliverPolicy> for consumer::DeliverPolicy {
+    fn from(policy: DeliverPolicy) -> Self {
+        match policy {
+            DeliverPolicy::All => Self::All,
+            DeliverPolicy::Last => Self::Last,
+            DeliverPolicy::New => Self::New,
+            DeliverPolicy::LastPerSubject => Self::LastPerSubject,
+            DeliverPolicy::ByStartSequence(start_sequence) => Self::ByStartSequence { start_sequence },
+        }
+    }
+}
+
+/// An error loading the [`Config`].
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+    /// An environment variable with an invalid or missing value.
+    Env(&'static str, String),
+    /// Two environment variables that cannot be set together.
+    Conflict(&'static str, &'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, error) => write!(f, "cannot read {}: {error}", path.display()),
+            Self::Parse(path, error) => write!(f, "invalid {}: {error}", path.display()),
+            Self::Env(name, value) if value.is_empty() => write!(f, "{name} is not set"),
+            Self::Env(name, value) => write!(f, "invalid {name}: {value:?}"),
+            Self::Conflict(first, second) => write!(f, "{first} and {second} are both set"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Returns the non-empty value of an environment variable.
+fn var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Splits a comma-separated list.
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
@@ -1 +1,2 @@
+pub mod config;
 pub mod nats;
\ No newline at end of file
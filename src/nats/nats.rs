@@ -1,32 +1,267 @@
-use std::{env, str::from_utf8};
-use futures::StreamExt;
+//! The JetStream requests of the `main_nats` inspector.
+//!
+//! Every function takes its own clone of the [`Context`], so it can run as a `Task`.
+use std::time::Duration;
 
-use async_nats::jetstream::{self, consumer::PullConsumer};
+use futures::{StreamExt, TryStreamExt};
 
-pub async fn get_nats_message() -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let nats_url = env::var("NATS_URL").unwrap_or_else(|_| "nats://43.206.231.234:4222".to_string());
+use async_nats::jetstream::consumer::{pull, AckPolicy, DeliverPolicy, PullConsumer};
+use async_nats::jetstream::{self, AckKind, Context};
 
-    let client = async_nats::connect(nats_url).await.unwrap();
+use super::config;
 
-    let mut vector = vec![];
+/// The most messages a single browse or fetch returns.
+pub const MAX_BATCH: usize = 200;
 
-    let jetstream = jetstream::new(client);
+/// How long an ephemeral browsing consumer outlives its last request.
+const BROWSE_INACTIVE_THRESHOLD: Duration = Duration::from_secs(30);
 
-    let consumer: PullConsumer = jetstream.get_stream("mm_perp_summary_derive").await.unwrap()
-    .create_consumer(jetstream::consumer::pull::Config {
-        durable_name: Some("consumer".into()),
-        ..Default::default()
-    })
-    .await.unwrap();
+/// The configuration and state of a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSummary {
+    pub name: String,
+    pub subjects: Vec<String>,
+    pub messages: u64,
+    pub bytes: u64,
+    pub first_sequence: u64,
+    pub last_sequence: u64,
+    pub consumers: usize,
+}
 
-    let mut messages = consumer.messages().await.unwrap().take(1);
+/// The state of a consumer of a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsumerSummary {
+    pub name: String,
+    pub durable: bool,
+    /// Messages of the stream not delivered yet.
+    pub pending: u64,
+    /// Messages delivered but not acknowledged yet.
+    pub ack_pending: usize,
+    pub delivered_sequence: u64,
+    pub ack_floor_sequence: u64,
+}
 
-    // Iterate over messages.
+/// A message stored in a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredMessage {
+    pub sequence: u64,
+    pub subject: String,
+    pub published: String,
+    pub headers: Vec<(String, String)>,
+    /// The payload, pretty-printed if it is JSON.
+    pub payload: String,
+}
+
+impl StoredMessage {
+    fn new(message: &jetstream::Message) -> Result<Self, async_nats::Error> {
+        let info = message.info()?;
+
+        let headers = message
+            .headers
+            .iter()
+            .flat_map(|headers| headers.iter())
+            .flat_map(|(name, values)| {
+                values.iter().map(move |value| (name.to_string(), value.as_str().to_string()))
+            })
+            .collect();
+
+        Ok(Self {
+            sequence: info.stream_sequence,
+            subject: message.subject.to_string(),
+            published: info.published.to_string(),
+            headers,
+            payload: pretty(&message.payload),
+        })
+    }
+}
+
+/// A message delivered by a consumer and waiting to be acknowledged.
+#[derive(Debug, Clone)]
+pub struct Delivered {
+    pub message: StoredMessage,
+    handle: jetstream::Message,
+}
+
+/// Which stored messages to browse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    /// The messages with sequences from `first` to `last`, inclusive.
+    Sequences { first: u64, last: u64 },
+    /// The messages published since this time.
+    Since(time::OffsetDateTime),
+}
+
+/// What to purge from a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Purge {
+    All,
+    Subject(String),
+    /// Every message before the sequence.
+    Before(u64),
+}
+
+/// Connects to the configured servers.
+pub async fn connect() -> Result<Context, async_nats::Error> {
+    let client = config::get().connect().await?;
+
+    Ok(jetstream::new(client))
+}
+
+pub async fn list_streams(context: Context) -> Result<Vec<StreamSummary>, async_nats::Error> {
+    let streams: Vec<_> = context.streams().try_collect().await?;
+
+    Ok(streams
+        .into_iter()
+        .map(|info| StreamSummary {
+            name: info.config.name,
+            subjects: info.config.subjects,
+            messages: info.state.messages,
+            bytes: info.state.bytes,
+            first_sequence: info.state.first_sequence,
+            last_sequence: info.state.last_sequence,
+            consumers: info.state.consumer_count,
+        })
+        .collect())
+}
+
+pub async fn list_consumers(
+    context: Context,
+    stream: String,
+) -> Result<Vec<ConsumerSummary>, async_nats::Error> {
+    let stream = context.get_stream(&stream).await?;
+    let consumers: Vec<_> = stream.consumers().try_collect().await?;
+
+    Ok(consumers
+        .into_iter()
+        .map(|info| ConsumerSummary {
+            durable: info.config.durable_name.is_some(),
+            name: info.name,
+            pending: info.num_pending,
+            ack_pending: info.num_ack_pending,
+            delivered_sequence: info.delivered.stream_sequence,
+            ack_floor_sequence: info.ack_floor.stream_sequence,
+        })
+        .collect())
+}
+
+/// Returns up to `limit` of the selected messages, oldest first, without acknowledging anything.
+///
+/// The messages are read through an ephemeral consumer, so no other consumer is affected.
+pub async fn browse(
+    context: Context,
+    stream: String,
+    selection: Selection,
+    limit: usize,
+) -> Result<Vec<StoredMessage>, async_nats::Error> {
+    let (deliver_policy, last, limit) = match selection {
+        Selection::Sequences { first, last } => (
+            DeliverPolicy::ByStartSequence { start_sequence: first },
+            last,
+            limit.min(usize::try_from(last.saturating_sub(first) + 1).unwrap_or(usize::MAX)),
+        ),
+        Selection::Since(start_time) => (
+            DeliverPolicy::ByStartTime { start_time },
+            u64::MAX,
+            limit,
+        ),
+    };
+
+    let consumer: PullConsumer = context
+        .get_stream(&stream)
+        .await?
+        .create_consumer(pull::Config {
+            deliver_policy,
+            ack_policy: AckPolicy::None,
+            inactive_threshold: BROWSE_INACTIVE_THRESHOLD,
+            ..Default::default()
+        })
+        .await?;
+
+    let mut messages = consumer
+        .fetch()
+        .max_messages(limit.clamp(1, MAX_BATCH))
+        .messages()
+        .await?;
+
+    let mut stored = vec![];
+    while let Some(message) = messages.next().await {
+        let message = StoredMessage::new(&message?)?;
+        // Deleted messages leave gaps, so the batch may run past the last sequence.
+        if message.sequence > last {
+            break;
+        }
+        stored.push(message);
+    }
+
+    Ok(stored)
+}
+
+/// Takes up to `limit` messages from a pull consumer, to be acknowledged with [`ack`].
+pub async fn fetch(
+    context: Context,
+    stream: String,
+    consumer: String,
+    limit: usize,
+) -> Result<Vec<Delivered>, async_nats::Error> {
+    let consumer = context
+        .get_stream(&stream)
+        .await?
+        .get_consumer::<pull::Config>(&consumer)
+        .await?;
+
+    let mut messages = consumer
+        .fetch()
+        .max_messages(limit.clamp(1, MAX_BATCH))
+        .messages()
+        .await?;
+
+    let mut delivered = vec![];
     while let Some(message) = messages.next().await {
-        let message = message.unwrap();
-        vector.push(from_utf8(&message.payload).unwrap().to_string());
-        message.ack().await.unwrap();
+        let handle = message?;
+        delivered.push(Delivered {
+            message: StoredMessage::new(&handle)?,
+            handle,
+        });
     }
 
-    Ok(vector)
-}
\ No newline at end of file
+    Ok(delivered)
+}
+
+pub async fn ack(delivered: Delivered, kind: AckKind) -> Result<(), async_nats::Error> {
+    delivered.handle.ack_with(kind).await
+}
+
+pub async fn delete(
+    context: Context,
+    stream: String,
+    sequence: u64,
+) -> Result<(), async_nats::Error> {
+    let _ = context.get_stream(&stream).await?.delete_message(sequence).await?;
+
+    Ok(())
+}
+
+/// Purges a stream and returns how many messages were removed.
+pub async fn purge(
+    context: Context,
+    stream: String,
+    purge: Purge,
+) -> Result<u64, async_nats::Error> {
+    let stream = context.get_stream(&stream).await?;
+
+    let response = match purge {
+        Purge::All => stream.purge().await?,
+        Purge::Subject(subject) => stream.purge().filter(subject).await?,
+        Purge::Before(sequence) => stream.purge().sequence(sequence).await?,
+    };
+
+    Ok(response.purged)
+}
+
+/// Pretty-prints a JSON payload, or returns it as text.
+fn pretty(payload: &[u8]) -> String {
+    serde_json::from_slice::<serde_json::Value>(payload)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| String::from_utf8_lossy(payload).into_owned())
+}
@@ -0,0 +1,436 @@
+//! Recording and replay of the NATS feed, so the dashboard can run without a network.
+//!
+//! The source of the feed is picked once at startup from the command line:
+//!
+//! | Argument | Source |
+//! |---|---|
+//! | none | The live feed |
+//! | `--record <file>` | The live feed, appending every payload to `<file>` |
+//! | `--replay <file>` | The payloads of `<file>`, at their recorded pace |
+//! | `--speed <factor>` | The initial replay speed, `1` by default |
+//!
+//! Recordings are newline-delimited JSON, one [`Record`] per line. Replayed snapshots are
+//! stamped with their recorded time, so charts, ages and alerts follow the recording whatever
+//! the replay speed.
+use std::collections::VecDeque;
+use std::env;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::future::{self, Either};
+use futures::sink::SinkExt;
+use futures::stream::{Stream, StreamExt};
+use iced::futures::channel::mpsc;
+use iced::stream;
+use iced::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::ws_handler;
+use crate::Message;
+
+/// The replay speeds offered by the dashboard.
+pub const SPEEDS: [f32; 6] = [0.5, 1.0, 2.0, 5.0, 10.0, 50.0];
+
+static SOURCE: OnceLock<Source> = OnceLock::new();
+
+/// Picks the [`Source`] from the command line. Call this once at startup.
+pub fn init() -> Result<&'static Source, Error> {
+    let source = Source::parse(env::args().skip(1))?;
+    Ok(SOURCE.get_or_init(|| source))
+}
+
+/// Returns the [`Source`] of the feed, the live one if [`init`] was not called.
+pub fn get() -> &'static Source {
+    SOURCE.get_or_init(|| Source::Live { record: None })
+}
+
+/// Where the dashboard gets its feed from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// The live JetStream consumer, recorded to a file if `record` is set.
+    Live { record: Option<PathBuf> },
+    /// A recording.
+    Replay { path: PathBuf, speed: f32 },
+}
+
+impl Source {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, Error> {
+        let mut record = None;
+        let mut replay = None;
+        let mut speed = 1.0;
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| Error::Argument(arg.clone()));
+
+            match arg.as_str() {
+                "--record" => record = Some(PathBuf::from(value()?)),
+                "--replay" => replay = Some(PathBuf::from(value()?)),
+                "--speed" => {
+                    let value = value()?;
+                    speed = value
+                        .parse()
+                        .ok()
+                        .filter(|speed: &f32| speed.is_finite() && *speed > 0.0)
+                        .ok_or(Error::Argument(format!("--speed {value}")))?;
+                }
+                _ => return Err(Error::Argument(arg)),
+            }
+        }
+
+        match (record, replay) {
+            (Some(_), Some(_)) => Err(Error::Argument("--record with --replay".to_string())),
+            (record, None) => Ok(Self::Live { record }),
+            (None, Some(path)) => Ok(Self::Replay { path, speed }),
+        }
+    }
+}
+
+/// A payload of the feed and when it was received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Milliseconds since the Unix epoch.
+    pub time: u64,
+    pub subject: String,
+    /// The payload, with invalid UTF-8 replaced.
+    pub payload: String,
+}
+
+/// Appends the payloads of the live feed to a recording.
+#[derive(Debug)]
+pub struct Recorder {
+    file: LineWriter<File>,
+}
+
+impl Recorder {
+    /// Opens a recording, appending to it if it exists.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file: LineWriter::new(file),
+        })
+    }
+
+    pub fn write(&mut self, subject: &str, payload: &[u8]) -> io::Result<()> {
+        let record = Record {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| u64::try_from(time.as_millis()).unwrap_or(u64::MAX)),
+            subject: subject.to_string(),
+            payload: String::from_utf8_lossy(payload).into_owned(),
+        };
+
+        serde_json::to_writer(&mut self.file, &record)?;
+        self.file.write_all(b"\n")
+    }
+}
+
+/// A [`Record`] of a loaded recording, timed from its first record.
+struct Entry {
+    offset: Duration,
+    record: Record,
+}
+
+/// Reads a recording, ordering its records by time.
+fn load(path: &Path) -> Result<Vec<Entry>, Error> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+
+    let mut records = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str::<Record>(line)
+                .map_err(|error| Error::Parse(path.to_owned(), index + 1, error))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    records.sort_by_key(|record| record.time);
+
+    let start = records.first().map_or(0, |record| record.time);
+
+    Ok(records
+        .into_iter()
+        .map(|record| Entry {
+            offset: Duration::from_millis(record.time - start),
+            record,
+        })
+        .collect())
+}
+
+/// An instruction for a running replay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Pause,
+    Resume,
+    Speed(f32),
+    /// Continues from the given time of the recording.
+    Seek(Duration),
+}
+
+/// Sends [`Command`]s to a running replay.
+#[derive(Debug, Clone)]
+pub struct Controls(mpsc::Sender<Command>);
+
+/// A change of a running replay.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Started {
+        controls: Controls,
+        duration: Duration,
+        /// The instant the start of the recording is replayed as.
+        start: Instant,
+    },
+    /// The time of the recording reached by the replay.
+    Progress(Duration),
+    Finished,
+    Failed(String),
+}
+
+/// What the dashboard knows about the replay.
+#[derive(Debug, Clone)]
+pub struct State {
+    controls: Option<Controls>,
+    /// Commands waiting for room in the controls channel.
+    pending: VecDeque<Command>,
+    start: Option<Instant>,
+    /// When `position` was last updated.
+    updated: Instant,
+    pub path: PathBuf,
+    pub duration: Duration,
+    pub position: Duration,
+    pub speed: f32,
+    pub paused: bool,
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+impl State {
+    pub fn new(path: PathBuf, speed: f32) -> Self {
+        Self {
+            controls: None,
+            pending: VecDeque::new(),
+            start: None,
+            updated: Instant::now(),
+            path,
+            duration: Duration::ZERO,
+            position: Duration::ZERO,
+            speed,
+            paused: false,
+            finished: false,
+            error: None,
+        }
+    }
+
+    pub fn update(&mut self, event: Event) {
+        match event {
+            Event::Started {
+                controls,
+                duration,
+                start,
+            } => {
+                self.controls = Some(controls);
+                self.duration = duration;
+                self.start = Some(start);
+            }
+            Event::Progress(position) => {
+                self.position = position;
+                self.updated = Instant::now();
+                self.finished = false;
+            }
+            Event::Finished => {
+                self.finished = true;
+            }
+            Event::Failed(error) => {
+                self.error = Some(error);
+            }
+        }
+
+        self.flush();
+    }
+
+    /// Sends a [`Command`] to the replay, once it has started and has room for it.
+    ///
+    /// A seek replaces a seek still waiting to be sent, so dragging the position does not
+    /// pile up commands.
+    pub fn send(&mut self, command: Command) {
+        match command {
+            Command::Pause => self.paused = true,
+            Command::Resume => self.paused = false,
+            Command::Speed(speed) => self.speed = speed,
+            Command::Seek(position) => {
+                self.position = position.min(self.duration);
+                self.updated = Instant::now();
+            }
+        }
+
+        match (self.pending.back_mut(), command) {
+            (Some(Command::Seek(pending)), Command::Seek(position)) => *pending = position,
+            _ => self.pending.push_back(command),
+        }
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        let Some(Controls(sender)) = &mut self.controls else {
+            return;
+        };
+
+        while let Some(command) = self.pending.pop_front() {
+            if let Err(error) = sender.try_send(command) {
+                if error.is_full() {
+                    self.pending.push_front(command);
+                } else {
+                    // The replay is gone, so there is nothing left to control.
+                    self.pending.clear();
+                }
+                return;
+            }
+        }
+    }
+
+    /// Returns the recorded time the replay has reached, if it has started.
+    pub fn clock(&self, now: Instant) -> Option<Instant> {
+        let mut position = self.position;
+        if !self.paused && !self.finished {
+            position += now.saturating_duration_since(self.updated).mul_f32(self.speed);
+        }
+
+        Some(self.start? + position.min(self.duration))
+    }
+}
+
+/// Streams the decoded payloads of the recording of the [`Source`], like
+/// [`ws_handler::connect`] does for the live feed.
+///
+/// The replay starts with [`Event::Started`], whose [`Controls`] pause, speed up and seek it.
+pub fn connect() -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        let Source::Replay { path, speed } = get() else {
+            return;
+        };
+
+        let entries = match load(path) {
+            Ok(entries) => entries,
+            Err(error) => {
+                eprintln!("Cannot replay: {error}");
+                let _ = output.send(Message::Replay(Event::Failed(error.to_string()))).await;
+                return;
+            }
+        };
+
+        let (sender, mut commands) = mpsc::channel(10);
+        let duration = entries.last().map_or(Duration::ZERO, |entry| entry.offset);
+        let start = Instant::now();
+        let _ = output
+            .send(Message::Replay(Event::Started {
+                controls: Controls(sender),
+                duration,
+                start,
+            }))
+            .await;
+
+        let mut index = 0;
+        let mut position = Duration::ZERO;
+        let mut speed = *speed;
+        let mut paused = false;
+
+        loop {
+            let command = match entries.get(index) {
+                Some(entry) if !paused => {
+                    let wait = entry.offset.saturating_sub(position).div_f32(speed);
+                    let started = Instant::now();
+
+                    match future::select(pin!(tokio::time::sleep(wait)), commands.next()).await {
+                        Either::Left(_) => {
+                            position = entry.offset;
+                            index += 1;
+
+                            let record = &entry.record;
+                            let time = start + entry.offset;
+                            let mut forwarded = ws_handler::decode(
+                                &record.subject,
+                                record.payload.as_bytes(),
+                                time,
+                            )
+                            .unwrap_or_else(|forwarded| forwarded);
+                            forwarded.push(Message::Replay(Event::Progress(position)));
+                            if index == entries.len() {
+                                forwarded.push(Message::Replay(Event::Finished));
+                            }
+
+                            for message in forwarded {
+                                if output.send(message).await.is_err() {
+                                    return;
+                                }
+                            }
+                            continue;
+                        }
+                        Either::Right((command, _)) => {
+                            position = (position + started.elapsed().mul_f32(speed)).min(entry.offset);
+                            command
+                        }
+                    }
+                }
+                _ => commands.next().await,
+            };
+
+            // The dashboard dropped the controls.
+            let Some(command) = command else {
+                return;
+            };
+
+            // Every queued command is applied before reporting the progress once, so a burst
+            // of seeks moves straight to the last one.
+            let mut next = Some(command);
+            while let Some(command) = next {
+                match command {
+                    Command::Pause => paused = true,
+                    Command::Resume => paused = false,
+                    Command::Speed(new_speed) if new_speed.is_finite() && new_speed > 0.0 => {
+                        speed = new_speed;
+                    }
+                    Command::Speed(_) => {}
+                    Command::Seek(target) => {
+                        position = target.min(duration);
+                        index = entries.partition_point(|entry| entry.offset < position);
+                    }
+                }
+                next = commands.try_next().ok().flatten();
+            }
+
+            if output.send(Message::Replay(Event::Progress(position))).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// An error picking the [`Source`] or loading a recording.
+#[derive(Debug)]
+pub enum Error {
+    /// An unknown command-line argument, or one with an invalid or missing value.
+    Argument(String),
+    Io(PathBuf, io::Error),
+    /// An invalid record, with its line number.
+    Parse(PathBuf, usize, serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Argument(argument) => write!(f, "invalid argument: {argument}"),
+            Self::Io(path, error) => write!(f, "cannot read {}: {error}", path.display()),
+            Self::Parse(path, line, error) => {
+                write!(f, "invalid record at {}:{line}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use iced::mouse;
+use iced::time::{Duration, Instant};
+use iced::widget::canvas::{self, Cache, Frame, Geometry, Path, Stroke};
+use iced::{Color, Pixels, Point, Rectangle, Renderer, Theme};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{History, Instrument};
+use crate::stream_data::StreamData;
+use crate::Message;
+
+/// The [`StreamData`] field a [`Sparkline`] plots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    #[default]
+    UnrealizedPnl,
+    Spread,
+    IndexPrice,
+    NetNotional,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [
+        Field::UnrealizedPnl,
+        Field::Spread,
+        Field::IndexPrice,
+        Field::NetNotional,
+    ];
+
+    pub fn value(self, data: &StreamData) -> f64 {
+        match self {
+            Field::UnrealizedPnl => data.unrealized_pnl,
+            Field::Spread => data.spread,
+            Field::IndexPrice => data.index_price,
+            Field::NetNotional => data.long_usd_notional - data.short_usd_notional,
+        }
+    }
+
+    /// Formats a value of the field for a label.
+    ///
+    /// Spreads and prices keep five significant digits, so small ones do not round to zero.
+    pub fn format(self, value: f64) -> String {
+        let decimals = match self {
+            Field::UnrealizedPnl | Field::NetNotional => 2,
+            Field::Spread | Field::IndexPrice if value.is_normal() => {
+                let magnitude = value.abs().log10().floor() as i32;
+                (4 - magnitude).clamp(2, 10) as usize
+            }
+            Field::Spread | Field::IndexPrice => 2,
+        };
+        format!("{value:.decimals$}")
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Field::UnrealizedPnl => "Unrealized PnL",
+            Field::Spread => "Spread",
+            Field::IndexPrice => "Index price",
+            Field::NetNotional => "Net notional",
+        })
+    }
+}
+
+/// How far back a [`Window::Session`] reaches at most, and so how long the store keeps the
+/// samples of an instrument.
+pub const SESSION_LENGTH: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// How far back a [`Sparkline`] plots, counted from the newest sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Window {
+    OneMinute,
+    #[default]
+    FiveMinutes,
+    FifteenMinutes,
+    /// Every sample since the dashboard started, up to [`SESSION_LENGTH`] back.
+    Session,
+}
+
+impl Window {
+    pub const ALL: [Window; 4] = [
+        Window::OneMinute,
+        Window::FiveMinutes,
+        Window::FifteenMinutes,
+        Window::Session,
+    ];
+
+    fn duration(self) -> Option<Duration> {
+        match self {
+            Window::OneMinute => Some(Duration::from_secs(60)),
+            Window::FiveMinutes => Some(Duration::from_secs(5 * 60)),
+            Window::FifteenMinutes => Some(Duration::from_secs(15 * 60)),
+            Window::Session => None,
+        }
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Window::OneMinute => "1 minute",
+            Window::FiveMinutes => "5 minutes",
+            Window::FifteenMinutes => "15 minutes",
+            Window::Session => "Session",
+        })
+    }
+}
+
+/// The selected [`Field`] and [`Window`] of the sparklines and the cached drawing of every
+/// instrument.
+///
+/// A cached drawing is only redrawn after its instrument received a new sample or the
+/// selection changed.
+#[derive(Default)]
+pub struct Charts {
+    pub field: Field,
+    pub window: Window,
+    caches: HashMap<Instrument, Cache>,
+}
+
+impl Charts {
+    pub fn select_field(&mut self, field: Field) {
+        self.field = field;
+        self.caches.values().for_each(|cache| cache.clear());
+    }
+
+    pub fn select_window(&mut self, window: Window) {
+        self.window = window;
+        self.caches.values().for_each(|cache| cache.clear());
+    }
+
+    /// Marks the drawing of an instrument as outdated after it received a new sample.
+    pub fn invalidate(&mut self, instrument: &Instrument) {
+        if let Some(cache) = self.caches.get(instrument) {
+            cache.clear();
+        }
+    }
+
+    /// Returns the [`Sparkline`] of the history of an instrument.
+    ///
+    /// Call [`Charts::prepare`] for the instrument first, as `view` cannot create its cache.
+    pub fn sparkline<'a>(&'a self, instrument: &Instrument, history: &'a History) -> Option<Sparkline<'a>> {
+        Some(Sparkline {
+            history,
+            field: self.field,
+            window: self.window,
+            cache: self.caches.get(instrument)?,
+        })
+    }
+
+    /// Creates the cache of an instrument, if it has none yet.
+    pub fn prepare(&mut self, instrument: &Instrument) {
+        if !self.caches.contains_key(instrument) {
+            let _ = self.caches.insert(instrument.clone(), Cache::new());
+        }
+    }
+}
+
+impl fmt::Debug for Charts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Charts")
+            .field("field", &self.field)
+            .field("window", &self.window)
+            .field("instruments", &self.caches.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A line chart of one [`Field`] of a [`History`], annotated with its minimum, maximum and
+/// latest value.
+pub struct Sparkline<'a> {
+    history: &'a History,
+    field: Field,
+    window: Window,
+    cache: &'a Cache,
+}
+
+impl canvas::Program<Message> for Sparkline<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            self.draw_chart(frame, theme);
+        });
+
+        vec![geometry]
+    }
+}
+
+impl Sparkline<'_> {
+    const LABEL_SIZE: f32 = 10.0;
+
+    fn draw_chart(&self, frame: &mut Frame, theme: &Theme) {
+        let palette = theme.extended_palette();
+        let end = self.history.latest().time;
+        let start = self.window.duration().and_then(|window| end.checked_sub(window));
+
+        let points: Vec<(Instant, f64)> = self
+            .history
+            .values(|data| self.field.value(data))
+            .filter(|(time, _)| start.is_none_or(|start| *time >= start))
+            .collect();
+
+        let Some(&(first, _)) = points.first() else {
+            return;
+        };
+        let (min, max) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (_, value)| {
+                (min.min(*value), max.max(*value))
+            });
+
+        let size = frame.size();
+        let plot = Rectangle {
+            x: 0.0,
+            y: Self::LABEL_SIZE + 2.0,
+            width: size.width,
+            height: (size.height - 2.0 * (Self::LABEL_SIZE + 2.0)).max(1.0),
+        };
+        let span = start.map_or(end.saturating_duration_since(first), |start| end - start);
+        let origin = start.unwrap_or(first);
+
+        let project = |time: Instant, value: f64| {
+            let x = if span.is_zero() {
+                plot.width
+            } else {
+                time.saturating_duration_since(origin).as_secs_f32() / span.as_secs_f32() * plot.width
+            };
+            let y = if max > min {
+                ((max - value) / (max - min)) as f32 * plot.height
+            } else {
+                plot.height / 2.0
+            };
+            Point::new(plot.x + x, plot.y + y)
+        };
+
+        let line = Path::new(|builder| {
+            for (index, (time, value)) in points.iter().enumerate() {
+                let point = project(*time, *value);
+                if index == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &line,
+            Stroke::default()
+                .with_color(palette.primary.strong.color)
+                .with_width(1.5),
+        );
+
+        let marker = |frame: &mut Frame, (time, value): (Instant, f64), color: Color| {
+            frame.fill(&Path::circle(project(time, value), 2.5), color);
+        };
+        let extreme = |target: f64| {
+            points
+                .iter()
+                .copied()
+                .find(|(_, value)| *value == target)
+                .unwrap_or(points[0])
+        };
+        marker(frame, extreme(max), palette.success.base.color);
+        marker(frame, extreme(min), palette.danger.base.color);
+
+        let label = |frame: &mut Frame, content: String, position: Point, color: Color| {
+            frame.fill_text(canvas::Text {
+                content,
+                position,
+                color,
+                size: Pixels(Self::LABEL_SIZE),
+                ..canvas::Text::default()
+            });
+        };
+        let text_color = palette.background.base.text;
+        let field = self.field;
+        label(frame, format!("max {}", field.format(max)), Point::ORIGIN, text_color);
+        label(
+            frame,
+            format!("min {}", field.format(min)),
+            Point::new(0.0, size.height - Self::LABEL_SIZE),
+            text_color,
+        );
+        if let Some(&(_, latest)) = points.last() {
+            label(
+                frame,
+                field.format(latest),
+                Point::new((size.width - 60.0).max(0.0), 0.0),
+                palette.primary.strong.color,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Field;
+
+    #[test]
+    fn small_spreads_and_prices_keep_their_digits() {
+        assert_eq!(Field::Spread.format(0.000_123_45), "0.00012345");
+        assert_eq!(Field::IndexPrice.format(0.5), "0.50000");
+        assert_eq!(Field::IndexPrice.format(60_000.5), "60000.50");
+        assert_eq!(Field::Spread.format(0.0), "0.00");
+        assert_eq!(Field::UnrealizedPnl.format(1_234.567), "1234.57");
+    }
+}
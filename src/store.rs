@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use iced::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::stream_data::StreamData;
+
+/// A perp market, as identified by the `name` and `currency` of its [`StreamData`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Instrument {
+    pub name: String,
+    pub currency: String,
+}
+
+impl Instrument {
+    pub fn of(stream_data: &StreamData) -> Self {
+        Self {
+            name: stream_data.name.clone(),
+            currency: stream_data.currency.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.currency)
+    }
+}
+
+/// A [`StreamData`] snapshot and when it was received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub time: Instant,
+    pub data: StreamData,
+}
+
+/// The latest [`Sample`]s of an [`Instrument`], dropping the ones older than its retention.
+#[derive(Debug, Clone)]
+pub struct History {
+    samples: VecDeque<Sample>,
+    retention: Duration,
+}
+
+impl History {
+    fn new(retention: Duration) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            retention,
+        }
+    }
+
+    fn push(&mut self, sample: Sample) {
+        // A replay seeking backwards starts the timeline over from the new sample.
+        while self.samples.back().is_some_and(|last| last.time > sample.time) {
+            let _ = self.samples.pop_back();
+        }
+
+        let oldest = sample.time.checked_sub(self.retention);
+        self.samples.push_back(sample);
+
+        if let Some(oldest) = oldest {
+            while self.samples.front().is_some_and(|sample| sample.time < oldest) {
+                let _ = self.samples.pop_front();
+            }
+        }
+    }
+
+    /// Returns the newest [`Sample`].
+    pub fn latest(&self) -> &Sample {
+        self.samples.back().expect("a history has at least one sample")
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the samples, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Sample> + ExactSizeIterator {
+        self.samples.iter()
+    }
+
+    /// Returns one field of every sample, oldest first.
+    pub fn values<F>(&self, field: F) -> impl Iterator<Item = (Instant, f64)>
+    where
+        F: Fn(&StreamData) -> f64,
+    {
+        self.samples.iter().map(move |sample| (sample.time, field(&sample.data)))
+    }
+}
+
+/// The [`History`] of every [`Instrument`] of the feed, in order of name and currency.
+#[derive(Debug, Clone)]
+pub struct Store {
+    instruments: BTreeMap<Instrument, History>,
+    retention: Duration,
+}
+
+impl Store {
+    /// Creates an empty [`Store`] keeping the samples of the last `retention` per instrument.
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            instruments: BTreeMap::new(),
+            retention,
+        }
+    }
+
+    /// Records a snapshot as the latest one of its [`Instrument`].
+    pub fn insert(&mut self, data: StreamData, time: Instant) {
+        let retention = self.retention;
+        self.instruments
+            .entry(Instrument::of(&data))
+            .or_insert_with(|| History::new(retention))
+            .push(Sample { time, data });
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    pub fn history(&self, instrument: &Instrument) -> Option<&History> {
+        self.instruments.get(instrument)
+    }
+
+    pub fn latest(&self, instrument: &Instrument) -> Option<&Sample> {
+        self.history(instrument).map(History::latest)
+    }
+
+    /// Returns every instrument and its history, in order of name and currency.
+    pub fn iter(&self) -> impl Iterator<Item = (&Instrument, &History)> {
+        self.instruments.iter()
+    }
+
+    /// Returns the time of the newest sample of any instrument.
+    pub fn last_update(&self) -> Option<Instant> {
+        self.instruments.values().map(|history| history.latest().time).max()
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use iced::time::Instant;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// The newest schema version of [`StreamData`] this dashboard knows.
+///
+/// Publishers that predate versioning send no `schema_version`, which counts as version 1.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A summary of the `mm_perp_summary_derive` stream.
+///
+/// Missing, `null` or numeric string numbers are tolerated and decode to their default, and
+/// unknown fields from newer publishers are ignored. The `name` and `currency` identifying the
+/// instrument are required by [`decode`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamData {
+    #[serde(deserialize_with = "version")]
+    pub schema_version: u32,
+    #[serde(deserialize_with = "lenient")]
+    pub name: String,
+    #[serde(deserialize_with = "lenient")]
+    pub currency: String,
+    #[serde(deserialize_with = "number")]
+    pub index_price: f64,
+    #[serde(deserialize_with = "number")]
+    pub unrealized_pnl: f64,
+    #[serde(deserialize_with = "number")]
+    pub spread: f64,
+    #[serde(deserialize_with = "number")]
+    pub long_ave_entry_price: f64,
+    #[serde(deserialize_with = "number")]
+    pub short_ave_entry_price: f64,
+    #[serde(deserialize_with = "number")]
+    pub long_size: f64,
+    #[serde(deserialize_with = "number")]
+    pub long_usd_notional: f64,
+    #[serde(deserialize_with = "number")]
+    pub short_size: f64,
+    #[serde(deserialize_with = "number")]
+    pub short_usd_notional: f64,
+    #[serde(deserialize_with = "number")]
+    pub dtd_pnl: f64,
+    #[serde(deserialize_with = "number")]
+    pub wtd_pnl: f64,
+    #[serde(deserialize_with = "number")]
+    pub mtd_pnl: f64,
+    #[serde(deserialize_with = "number")]
+    pub dtd_volume_maker: f64,
+    #[serde(deserialize_with = "number")]
+    pub dtd_volume_taker: f64,
+    #[serde(deserialize_with = "number")]
+    pub wtd_volume_maker: f64,
+    #[serde(deserialize_with = "number")]
+    pub wtd_volume_taker: f64,
+    #[serde(deserialize_with = "number")]
+    pub mtd_volume_maker: f64,
+    #[serde(deserialize_with = "number")]
+    pub mtd_volume_taker: f64,
+}
+
+/// The numeric fields every publisher of [`SCHEMA_VERSION`] sends.
+const NUMBERS: &[&str] = &[
+    "index_price",
+    "unrealized_pnl",
+    "spread",
+    "long_ave_entry_price",
+    "short_ave_entry_price",
+    "long_size",
+    "long_usd_notional",
+    "short_size",
+    "short_usd_notional",
+    "dtd_pnl",
+    "wtd_pnl",
+    "mtd_pnl",
+    "dtd_volume_maker",
+    "dtd_volume_taker",
+    "wtd_volume_maker",
+    "wtd_volume_taker",
+    "mtd_volume_maker",
+    "mtd_volume_taker",
+];
+
+impl Default for StreamData {
+    fn default() -> Self {
+        Self {
+            schema_version: 1,
+            name: String::new(),
+            currency: String::new(),
+            index_price: 0.0,
+            unrealized_pnl: 0.0,
+            spread: 0.0,
+            long_ave_entry_price: 0.0,
+            short_ave_entry_price: 0.0,
+            long_size: 0.0,
+            long_usd_notional: 0.0,
+            short_size: 0.0,
+            short_usd_notional: 0.0,
+            dtd_pnl: 0.0,
+            wtd_pnl: 0.0,
+            mtd_pnl: 0.0,
+            dtd_volume_maker: 0.0,
+            dtd_volume_taker: 0.0,
+            wtd_volume_maker: 0.0,
+            wtd_volume_taker: 0.0,
+            mtd_volume_maker: 0.0,
+            mtd_volume_taker: 0.0,
+        }
+    }
+}
+
+/// Decodes a payload into [`StreamData`], along with warnings about numbers it had to make up,
+/// because they were missing or not numbers.
+///
+/// Payloads that are not a JSON object, or do not name their instrument and its currency, fail
+/// to decode.
+pub fn decode(payload: &[u8]) -> Result<(StreamData, Vec<String>), String> {
+    let value: Value = serde_json::from_slice(payload).map_err(|error| format!("invalid JSON: {error}"))?;
+    let Value::Object(fields) = &value else {
+        return Err(format!("expected an object, got {}", kind(&value)));
+    };
+
+    let mut warnings = Vec::new();
+
+    let missing: Vec<&str> = NUMBERS
+        .iter()
+        .copied()
+        .filter(|field| fields.get(*field).is_none_or(Value::is_null))
+        .collect();
+    if !missing.is_empty() {
+        warnings.push(format!("missing fields: {}", missing.join(", ")));
+    }
+
+    let invalid: Vec<String> = NUMBERS
+        .iter()
+        .filter_map(|field| {
+            let value = fields.get(*field).filter(|value| !value.is_null())?;
+            number_of(value).is_none().then(|| format!("{field} = {value}"))
+        })
+        .collect();
+    if !invalid.is_empty() {
+        warnings.push(format!("invalid numbers, read as 0: {}", invalid.join(", ")));
+    }
+
+    let stream_data: StreamData = serde_json::from_value(value).map_err(|error| error.to_string())?;
+    if stream_data.name.trim().is_empty() {
+        return Err("missing name".to_string());
+    }
+    if stream_data.currency.trim().is_empty() {
+        return Err("missing currency".to_string());
+    }
+    if stream_data.schema_version > SCHEMA_VERSION {
+        warnings.push(format!(
+            "schema version {} is newer than {SCHEMA_VERSION}, unknown fields were ignored",
+            stream_data.schema_version
+        ));
+    }
+
+    Ok((stream_data, warnings))
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Deserializes a schema version, falling back to version 1 if it is not a number.
+fn version<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Value::deserialize(deserializer)?
+        .as_u64()
+        .map_or(1, |version| u32::try_from(version).unwrap_or(u32::MAX)))
+}
+
+/// Deserializes a number that may be sent as a string or `null`.
+fn number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(number_of(&Value::deserialize(deserializer)?).unwrap_or_default())
+}
+
+/// Returns the number in a value, if it is one or a string holding one.
+fn number_of(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Deserializes a string that may be `null`.
+fn lenient<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(string) => string,
+        Value::Null => String::new(),
+        other => other.to_string(),
+    })
+}
+
+/// How bad a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The payload was decoded, but not completely.
+    Warning,
+    /// The payload was dropped.
+    Error,
+}
+
+/// A problem with a payload of the feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub time: Instant,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn warning(message: String) -> Self {
+        Self {
+            time: Instant::now(),
+            severity: Severity::Warning,
+            message,
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self {
+            time: Instant::now(),
+            severity: Severity::Error,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// The latest [`Diagnostic`]s, dropping the oldest ones past its capacity.
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    entries: VecDeque<Diagnostic>,
+    capacity: usize,
+}
+
+impl Diagnostics {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        if self.entries.len() == self.capacity {
+            let _ = self.entries.pop_front();
+        }
+        if self.capacity > 0 {
+            self.entries.push_back(diagnostic);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.entries.iter().rev()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, SCHEMA_VERSION};
+
+    #[test]
+    fn complete_payload_decodes_without_warnings() {
+        let payload = br#"{
+            "name": "BTC-PERP", "currency": "BTC", "index_price": 60000.5,
+            "unrealized_pnl": 1, "spread": 2, "long_ave_entry_price": 3,
+            "short_ave_entry_price": 4, "long_size": 5, "long_usd_notional": 6,
+            "short_size": 7, "short_usd_notional": 8, "dtd_pnl": 9, "wtd_pnl": 10,
+            "mtd_pnl": 11, "dtd_volume_maker": 12, "dtd_volume_taker": 13,
+            "wtd_volume_maker": 14, "wtd_volume_taker": 15, "mtd_volume_maker": 16,
+            "mtd_volume_taker": 17
+        }"#;
+
+        let (data, warnings) = decode(payload).unwrap();
+
+        assert_eq!(data.name, "BTC-PERP");
+        assert_eq!(data.index_price, 60000.5);
+        assert_eq!(data.mtd_volume_taker, 17.0);
+        assert_eq!(data.schema_version, 1);
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn missing_numbers_are_zero_with_a_warning() {
+        let (data, warnings) =
+            decode(br#"{"name": "ETH-PERP", "currency": "ETH", "spread": null}"#).unwrap();
+
+        assert_eq!(data.spread, 0.0);
+        assert_eq!(data.index_price, 0.0);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("missing fields: index_price"));
+        assert!(warnings[0].contains("spread"));
+        assert!(!warnings[0].contains("name"));
+    }
+
+    #[test]
+    fn missing_name_or_currency_fails() {
+        assert_eq!(decode(b"{}").unwrap_err(), "missing name");
+        assert_eq!(decode(br#"{"foo": 1}"#).unwrap_err(), "missing name");
+        assert_eq!(
+            decode(br#"{"name": "SOL-PERP", "currency": ""}"#).unwrap_err(),
+            "missing currency"
+        );
+        assert_eq!(
+            decode(br#"{"name": null, "currency": "SOL"}"#).unwrap_err(),
+            "missing name"
+        );
+    }
+
+    #[test]
+    fn numbers_may_be_strings() {
+        let payload = br#"{
+            "name": "BTC-PERP", "currency": "BTC", "index_price": " 60000.25 ", "spread": "x",
+            "long_size": true
+        }"#;
+
+        let (data, warnings) = decode(payload).unwrap();
+
+        assert_eq!(data.index_price, 60000.25);
+        assert_eq!(data.spread, 0.0);
+        assert_eq!(data.long_size, 0.0);
+        let invalid = r#"invalid numbers, read as 0: spread = "x", long_size = true"#;
+        assert!(warnings.iter().any(|warning| warning == invalid), "{warnings:?}");
+    }
+
+    #[test]
+    fn newer_schema_version_is_decoded_with_a_warning() {
+        let payload = format!(
+            r#"{{"schema_version": {}, "name": "BTC-PERP", "currency": "BTC", "new_field": true}}"#,
+            SCHEMA_VERSION + 1
+        );
+
+        let (data, warnings) = decode(payload.as_bytes()).unwrap();
+
+        assert_eq!(data.schema_version, SCHEMA_VERSION + 1);
+        assert!(warnings.iter().any(|warning| warning.starts_with("schema version 2 is newer")));
+    }
+
+    #[test]
+    fn non_objects_fail() {
+        assert_eq!(decode(b"[1, 2]").unwrap_err(), "expected an object, got an array");
+        assert_eq!(decode(b"null").unwrap_err(), "expected an object, got null");
+        assert_eq!(decode(b"\"BTC\"").unwrap_err(), "expected an object, got a string");
+        assert!(decode(b"{").unwrap_err().starts_with("invalid JSON"));
+    }
+}
@@ -0,0 +1,538 @@
+//! Interaction tests of the [`Column`] that feed synthetic events through
+//! [`Widget::update`] with a headless renderer, and layout tests of every [`Direction`].
+
+use super::*;
+
+use iced::advanced::clipboard;
+use iced::widget::Space;
+
+const ITEM_WIDTH: f32 = 100.0;
+const ITEM_HEIGHT: f32 = 20.0;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    Grab(usize),
+    Drag(usize, usize),
+    Drop(usize, usize),
+    DropMany(Vec<usize>, usize),
+    Cancel(usize),
+}
+
+/// `count` fixed size items keyed by their index.
+fn items(count: usize) -> impl Iterator<Item = (usize, Element<'static, Message, Theme, ()>)> {
+    (0..count).map(|index| (index, Space::new(ITEM_WIDTH, ITEM_HEIGHT).into()))
+}
+
+/// A [`Column`] of `count` [`items`], publishing every callback.
+fn column(count: usize) -> Column<'static, usize, Message, Theme, ()> {
+    Column::with_children(items(count))
+        .on_grab(Message::Grab)
+        .on_drag(Message::Drag)
+        .on_drop(Message::Drop)
+        .on_cancel(Message::Cancel)
+}
+
+/// The center of the item at `index` of a [`column`] with the given spacing.
+fn center(index: usize, spacing: f32) -> Point {
+    Point::new(
+        ITEM_WIDTH / 2.0,
+        index as f32 * (ITEM_HEIGHT + spacing) + ITEM_HEIGHT / 2.0,
+    )
+}
+
+/// A point in the upper half of the item at `index` of a [`column`] without spacing, which
+/// drops before it.
+fn upper_half(index: usize) -> Point {
+    center(index, 0.0) - Vector::new(0.0, ITEM_HEIGHT / 4.0)
+}
+
+/// A laid out widget with its tree and cursor, driven by synthetic events.
+struct Harness {
+    element: Element<'static, Message, Theme, ()>,
+    tree: Tree,
+    node: layout::Node,
+    cursor: mouse::Cursor,
+}
+
+impl Harness {
+    fn new(widget: impl Into<Element<'static, Message, Theme, ()>>) -> Self {
+        let element = widget.into();
+        let mut tree = Tree::new(&element);
+        let limits = layout::Limits::new(Size::ZERO, Size::new(1000.0, 1000.0));
+        let node = element.as_widget().layout(&mut tree, &(), &limits);
+        Self {
+            element,
+            tree,
+            node,
+            cursor: mouse::Cursor::Unavailable,
+        }
+    }
+
+    fn state(&self) -> &State<usize> {
+        self.tree.state.downcast_ref::<State<usize>>()
+    }
+
+    fn layout(&self) -> Layout<'_> {
+        Layout::new(&self.node)
+    }
+
+    /// Feeds an event through [`Widget::update`] and returns the published messages.
+    fn update(&mut self, event: Event) -> Vec<Message> {
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        self.element.as_widget_mut().update(
+            &mut self.tree,
+            &event,
+            Layout::new(&self.node),
+            self.cursor,
+            &(),
+            &mut clipboard::Null,
+            &mut shell,
+            &Rectangle::with_size(Size::INFINITY),
+        );
+        messages
+    }
+
+    fn press(&mut self, position: Point) -> Vec<Message> {
+        self.cursor = mouse::Cursor::Available(position);
+        self.update(Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Left,
+        )))
+    }
+
+    fn move_to(&mut self, position: Point) -> Vec<Message> {
+        self.cursor = mouse::Cursor::Available(position);
+        self.update(Event::Mouse(mouse::Event::CursorMoved { position }))
+    }
+
+    fn release(&mut self) -> Vec<Message> {
+        self.update(Event::Mouse(mouse::Event::ButtonReleased(
+            mouse::Button::Left,
+        )))
+    }
+
+    fn right_click(&mut self) -> Vec<Message> {
+        self.update(Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Right,
+        )))
+    }
+
+    fn modifiers(&mut self, modifiers: keyboard::Modifiers) -> Vec<Message> {
+        self.update(Event::Keyboard(keyboard::Event::ModifiersChanged(
+            modifiers,
+        )))
+    }
+
+    fn focus(&mut self) {
+        operation::Focusable::focus(self.tree.state.downcast_mut::<State<usize>>());
+    }
+
+    fn key(&mut self, named: keyboard::key::Named) -> Vec<Message> {
+        let key = keyboard::Key::Named(named);
+        self.update(Event::Keyboard(keyboard::Event::KeyPressed {
+            key: key.clone(),
+            modified_key: key,
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Standard,
+            modifiers: keyboard::Modifiers::default(),
+            text: None,
+        }))
+    }
+
+    fn redraw(&mut self, now: Instant) -> Vec<Message> {
+        self.update(Event::Window(window::Event::RedrawRequested(now)))
+    }
+
+    fn touch(
+        &mut self,
+        event: fn(touch::Finger, Point) -> touch::Event,
+        position: Point,
+    ) -> Vec<Message> {
+        self.cursor = mouse::Cursor::Available(position);
+        self.update(Event::Touch(event(touch::Finger(0), position)))
+    }
+}
+
+#[test]
+fn drag_and_drop_publishes_grab_drag_and_drop() {
+    let mut harness = Harness::new(column(3));
+
+    assert_eq!(harness.press(center(0, 0.0)), [Message::Grab(0)]);
+    assert!(matches!(
+        harness.state().drag,
+        DragState::Grabbed { key: 0, .. }
+    ));
+
+    assert_eq!(
+        harness.move_to(center(1, 0.0) + Vector::new(0.0, 5.0)),
+        [Message::Drag(0, 2)]
+    );
+    assert!(
+        harness
+            .move_to(center(1, 0.0) + Vector::new(0.0, 6.0))
+            .is_empty()
+    );
+    assert_eq!(
+        harness.move_to(center(2, 0.0) + Vector::new(0.0, 5.0)),
+        [Message::Drag(0, 3)]
+    );
+    assert!(matches!(
+        harness.state().drag,
+        DragState::Dragged {
+            key: 0,
+            drop_location: 3,
+            ..
+        }
+    ));
+
+    assert_eq!(harness.release(), [Message::Drop(0, 3)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+}
+
+#[test]
+fn release_without_moving_cancels() {
+    let mut harness = Harness::new(column(3));
+
+    assert_eq!(harness.press(center(1, 0.0)), [Message::Grab(1)]);
+    assert_eq!(harness.release(), [Message::Cancel(1)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+}
+
+#[test]
+fn right_click_cancels_drag() {
+    let mut harness = Harness::new(column(3));
+
+    let _ = harness.press(center(2, 0.0));
+    assert_eq!(harness.move_to(upper_half(0)), [Message::Drag(2, 0)]);
+    assert_eq!(harness.right_click(), [Message::Cancel(2)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+    assert!(harness.release().is_empty());
+}
+
+#[test]
+fn press_outside_of_children_is_ignored() {
+    let mut harness = Harness::new(column(2).padding(10).width(300));
+
+    assert!(harness.press(Point::new(250.0, 15.0)).is_empty());
+    assert_eq!(harness.state().drag, DragState::Idle);
+    assert!(harness.move_to(Point::new(250.0, 40.0)).is_empty());
+    assert!(harness.release().is_empty());
+}
+
+#[test]
+fn touch_drag_and_drop() {
+    let mut harness = Harness::new(column(3));
+    let pressed = |id, position| touch::Event::FingerPressed { id, position };
+    let moved = |id, position| touch::Event::FingerMoved { id, position };
+    let lifted = |id, position| touch::Event::FingerLifted { id, position };
+
+    assert_eq!(harness.touch(pressed, center(2, 0.0)), [Message::Grab(2)]);
+    assert_eq!(harness.touch(moved, upper_half(0)), [Message::Drag(2, 0)]);
+    assert_eq!(harness.touch(lifted, upper_half(0)), [Message::Drop(2, 0)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+}
+
+#[test]
+fn lost_touch_cancels_drag() {
+    let mut harness = Harness::new(column(3));
+    let pressed = |id, position| touch::Event::FingerPressed { id, position };
+    let moved = |id, position| touch::Event::FingerMoved { id, position };
+    let lost = |id, position| touch::Event::FingerLost { id, position };
+
+    let _ = harness.touch(pressed, center(0, 0.0));
+    let _ = harness.touch(moved, center(2, 0.0));
+    assert_eq!(harness.touch(lost, center(2, 0.0)), [Message::Cancel(0)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+}
+
+#[test]
+fn dragged_position_is_locked_to_the_main_axis() {
+    let mut harness = Harness::new(column(3));
+
+    let _ = harness.press(center(0, 0.0));
+    let _ = harness.move_to(Point::new(400.0, 45.0));
+    let DragState::Dragged {
+        origin, position, ..
+    } = harness.state().drag
+    else {
+        panic!("expected a drag, got {:?}", harness.state().drag);
+    };
+    assert_eq!(position, Point::new(origin.x, 45.0));
+}
+
+#[test]
+fn lateral_drag_keeps_the_cursor_position() {
+    let mut harness = Harness::new(column(3).drag_lateral(true));
+
+    let _ = harness.press(center(0, 0.0));
+    let _ = harness.move_to(Point::new(400.0, 45.0));
+    assert!(matches!(
+        harness.state().drag,
+        DragState::Dragged { position, .. } if position == Point::new(400.0, 45.0)
+    ));
+}
+
+#[test]
+fn drag_center_starts_dragging_from_the_center_of_the_child() {
+    let mut harness = Harness::new(column(3).drag_center(true));
+
+    assert_eq!(
+        harness.press(Point::new(5.0, 2.0)),
+        [Message::Grab(0), Message::Drag(0, 0)]
+    );
+    assert!(matches!(
+        harness.state().drag,
+        DragState::Dragged { key: 0, origin, drop_location: 0, .. } if origin == center(0, 0.0)
+    ));
+    assert_eq!(harness.release(), [Message::Drop(0, 0)]);
+}
+
+#[test]
+fn drag_starts_past_the_threshold() {
+    let mut harness = Harness::new(column(3).drag_threshold(10));
+
+    let _ = harness.press(center(0, 0.0));
+    assert!(
+        harness
+            .move_to(center(0, 0.0) + Vector::new(3.0, 8.0))
+            .is_empty()
+    );
+    assert!(matches!(harness.state().drag, DragState::Grabbed { .. }));
+    assert_eq!(harness.release(), [Message::Cancel(0)]);
+
+    let _ = harness.press(center(0, 0.0));
+    assert_eq!(
+        harness.move_to(center(0, 0.0) + Vector::new(0.0, 12.0)),
+        [Message::Drag(0, 1)]
+    );
+    assert_eq!(harness.release(), [Message::Drop(0, 1)]);
+}
+
+#[test]
+fn long_press_starts_dragging_after_the_delay() {
+    let delay = Duration::from_secs(60);
+    let mut harness = Harness::new(column(3).long_press(Some(delay)));
+
+    let _ = harness.press(center(0, 0.0));
+    assert!(harness.redraw(Instant::now()).is_empty());
+    assert!(matches!(harness.state().drag, DragState::Grabbed { .. }));
+
+    assert_eq!(
+        harness.redraw(Instant::now() + delay),
+        [Message::Drag(0, 1)]
+    );
+    assert_eq!(harness.move_to(center(2, 0.0)), [Message::Drag(0, 3)]);
+    assert_eq!(harness.release(), [Message::Drop(0, 3)]);
+}
+
+#[test]
+fn moving_before_the_long_press_abandons_the_grab() {
+    let mut harness = Harness::new(column(3).long_press(Some(Duration::from_secs(60))));
+    let pressed = |id, position| touch::Event::FingerPressed { id, position };
+    let moved = |id, position| touch::Event::FingerMoved { id, position };
+
+    assert_eq!(harness.touch(pressed, center(0, 0.0)), [Message::Grab(0)]);
+    assert_eq!(harness.touch(moved, center(2, 0.0)), [Message::Cancel(0)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+}
+
+#[test]
+fn rejected_drop_location_cancels() {
+    let mut harness = Harness::new(
+        column(3)
+            .can_drag(|key| key != 0)
+            .can_drop(|_, drop_location| drop_location > 0),
+    );
+
+    assert!(harness.press(center(0, 0.0)).is_empty());
+    assert!(harness.release().is_empty());
+
+    let _ = harness.press(center(2, 0.0));
+    assert_eq!(harness.move_to(upper_half(0)), [Message::Drag(2, 0)]);
+    assert_eq!(harness.release(), [Message::Cancel(2)]);
+}
+
+#[test]
+fn selection_is_rejected_if_any_of_it_is() {
+    let mut harness = Harness::new(
+        column(3)
+            .on_drop_many(Message::DropMany)
+            .can_drop(|key, drop_location| key != 1 || drop_location > 0),
+    );
+
+    let _ = harness.press(center(1, 0.0));
+    let _ = harness.release();
+    let _ = harness.modifiers(keyboard::Modifiers::COMMAND);
+    let _ = harness.press(center(2, 0.0));
+    let _ = harness.release();
+    let _ = harness.modifiers(keyboard::Modifiers::default());
+    assert_eq!(harness.state().selection, [1, 2]);
+
+    let _ = harness.press(center(2, 0.0));
+    assert_eq!(harness.move_to(upper_half(0)), [Message::Drag(2, 0)]);
+    assert_eq!(harness.release(), [Message::Cancel(2)]);
+
+    let _ = harness.press(center(2, 0.0));
+    let _ = harness.move_to(upper_half(0));
+    let _ = harness.move_to(center(2, 0.0) + Vector::new(0.0, 5.0));
+    assert_eq!(harness.release(), [Message::DropMany(vec![1, 2], 3)]);
+}
+
+#[test]
+fn keyboard_pick_up_step_and_drop() {
+    use keyboard::key::Named::{ArrowDown, ArrowUp, Enter, Space};
+    let mut harness = Harness::new(column(3));
+
+    assert!(harness.key(Space).is_empty());
+    harness.focus();
+    assert!(harness.key(ArrowDown).is_empty());
+    assert_eq!(harness.state().focus, Some(1));
+    assert!(harness.key(ArrowUp).is_empty());
+    assert_eq!(harness.state().focus, Some(0));
+
+    assert_eq!(harness.key(Space), [Message::Grab(0), Message::Drag(0, 0)]);
+    assert_eq!(harness.key(ArrowDown), [Message::Drag(0, 2)]);
+    assert_eq!(harness.key(ArrowDown), [Message::Drag(0, 3)]);
+    assert!(harness.key(ArrowDown).is_empty());
+    assert_eq!(harness.key(ArrowUp), [Message::Drag(0, 2)]);
+    assert_eq!(harness.key(Enter), [Message::Drop(0, 2)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+    assert_eq!(harness.state().focus, Some(1));
+}
+
+#[test]
+fn keyboard_escape_cancels() {
+    use keyboard::key::Named::{ArrowDown, Escape, Space};
+    let mut harness = Harness::new(column(3));
+    harness.focus();
+
+    let _ = harness.key(Space);
+    assert_eq!(harness.key(ArrowDown), [Message::Drag(0, 2)]);
+    assert_eq!(harness.key(Escape), [Message::Cancel(0)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+    assert_eq!(harness.state().focus, Some(0));
+}
+
+#[test]
+fn keyboard_drop_at_a_rejected_location_cancels() {
+    use keyboard::key::Named::{ArrowDown, ArrowUp, Enter, Space};
+    let mut harness = Harness::new(column(3).can_drop(|_, drop_location| drop_location > 0));
+    harness.focus();
+
+    let _ = harness.key(ArrowDown);
+    assert_eq!(harness.key(Space), [Message::Grab(1), Message::Drag(1, 1)]);
+    assert_eq!(harness.key(ArrowUp), [Message::Drag(1, 0)]);
+    assert_eq!(harness.key(Enter), [Message::Cancel(1)]);
+    assert_eq!(harness.state().drag, DragState::Idle);
+
+    let _ = harness.key(Space);
+    let _ = harness.key(ArrowDown);
+    assert_eq!(harness.key(Enter), [Message::Drop(1, 3)]);
+}
+
+#[test]
+fn keyboard_drop_of_a_selection_focuses_the_picked_child() {
+    use keyboard::key::Named::{ArrowDown, Enter, Space};
+    let mut harness = Harness::new(column(4).on_drop_many(Message::DropMany));
+    harness.focus();
+
+    let _ = harness.press(center(1, 0.0));
+    let _ = harness.release();
+    let _ = harness.modifiers(keyboard::Modifiers::COMMAND);
+    let _ = harness.press(center(0, 0.0));
+    let _ = harness.release();
+    let _ = harness.modifiers(keyboard::Modifiers::default());
+    assert_eq!(harness.state().focus, Some(0));
+
+    let _ = harness.key(Space);
+    let _ = harness.key(ArrowDown);
+    let _ = harness.key(ArrowDown);
+    assert_eq!(harness.key(ArrowDown), [Message::Drag(0, 4)]);
+    assert_eq!(harness.key(Enter), [Message::DropMany(vec![0, 1], 4)]);
+    assert_eq!(harness.state().focus, Some(2));
+}
+
+#[test]
+fn zero_children() {
+    let mut harness = Harness::new(column(0).width(200).height(100));
+
+    assert!(harness.press(Point::new(50.0, 50.0)).is_empty());
+    assert!(harness.move_to(Point::new(60.0, 70.0)).is_empty());
+    assert!(harness.release().is_empty());
+    assert!(harness.right_click().is_empty());
+    assert_eq!(harness.state().drag, DragState::Idle);
+
+    let layout = harness.layout();
+    assert_eq!(Vertical.drop_location(&layout, Point::new(50.0, 50.0)), 0);
+    assert_eq!(Vertical.drop_marker(&layout, Padding::ZERO, 0.0, 0), None);
+}
+
+#[test]
+fn drop_location_and_marker_follow_the_children() {
+    let spacing = 10.0;
+    let harness = Harness::new(column(3).spacing(spacing));
+    let layout = harness.layout();
+
+    let drop_location = |y| Vertical.drop_location(&layout, Point::new(50.0, y));
+    assert_eq!(drop_location(-5.0), 0);
+    assert_eq!(drop_location(center(0, spacing).y - 1.0), 0);
+    assert_eq!(drop_location(center(0, spacing).y + 1.0), 1);
+    assert_eq!(drop_location(center(2, spacing).y - 1.0), 2);
+    assert_eq!(drop_location(500.0), 3);
+
+    let marker_y = |drop_location| {
+        Vertical
+            .drop_marker(&layout, Padding::ZERO, spacing, drop_location)
+            .map(|(start, end)| {
+                assert_eq!(start.y, end.y);
+                start.y
+            })
+    };
+    assert_eq!(marker_y(0), Some(-5.0));
+    assert_eq!(marker_y(1), Some(25.0));
+    assert_eq!(marker_y(3), Some(85.0));
+}
+
+#[test]
+fn row_drop_location_and_marker() {
+    let padding = Padding::new(5.0);
+    let harness = Harness::new(Row::with_children(items(3)).spacing(10).padding(padding));
+    let layout = harness.layout();
+
+    let drop_location = |x| Horizontal.drop_location(&layout, Point::new(x, 15.0));
+    assert_eq!(drop_location(50.0), 0);
+    assert_eq!(drop_location(60.0), 1);
+    assert_eq!(drop_location(170.0), 2);
+    assert_eq!(drop_location(500.0), 3);
+
+    let marker = |drop_location| Horizontal.drop_marker(&layout, padding, 10.0, drop_location);
+    assert_eq!(marker(0), Some((Point::new(0.0, 5.0), Point::new(0.0, 25.0))));
+    assert_eq!(marker(1), Some((Point::new(110.0, 5.0), Point::new(110.0, 25.0))));
+    assert_eq!(marker(3), Some((Point::new(330.0, 5.0), Point::new(330.0, 25.0))));
+}
+
+#[test]
+fn grid_drop_location_and_marker() {
+    let padding = Padding::new(10.0);
+    // Three items fit on a line of 330 pixels between the padding.
+    let harness =
+        Harness::new(Grid::with_children(items(5)).spacing(10).padding(padding).width(350));
+    let layout = harness.layout();
+
+    let drop_location = |x, y| Wrapping::default().drop_location(&layout, Point::new(x, y));
+    assert_eq!(drop_location(5.0, 20.0), 0);
+    assert_eq!(drop_location(65.0, 20.0), 1);
+    assert_eq!(drop_location(300.0, 20.0), 3);
+    assert_eq!(drop_location(65.0, 45.0), 4);
+    assert_eq!(drop_location(500.0, 500.0), 5);
+
+    let marker = |drop_location| {
+        Wrapping::default().drop_marker(&layout, padding, 10.0, drop_location)
+    };
+    assert_eq!(marker(0), Some((Point::new(10.0, 10.0), Point::new(10.0, 30.0))));
+    assert_eq!(marker(1), Some((Point::new(115.0, 10.0), Point::new(115.0, 30.0))));
+    assert_eq!(marker(3), Some((Point::new(10.0, 40.0), Point::new(10.0, 60.0))));
+    assert_eq!(marker(5), Some((Point::new(225.0, 40.0), Point::new(225.0, 60.0))));
+}
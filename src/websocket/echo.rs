@@ -1,115 +1,237 @@
+pub mod endpoint;
 pub mod server;
 
+pub use endpoint::Endpoint;
+
 use iced::futures;
 use iced::stream;
 use iced::widget::text;
 
 use futures::channel::mpsc;
+use futures::future::{self, FutureExt};
 use futures::sink::SinkExt;
 use futures::stream::{Stream, StreamExt};
 
 use async_tungstenite::tungstenite;
+use async_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use async_tungstenite::tungstenite::protocol::CloseFrame;
+use rand::Rng;
 use std::fmt;
+use tokio::time::{Duration, Instant};
+
+/// The delay before the first reconnection attempt, doubled on every further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
-pub fn connect() -> impl Stream<Item = Event> {
+/// How often to ping the server while connected.
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+/// How long to wait for the pong before giving up on the connection.
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Streams the connection [`Event`]s and echoed messages of the echo server at the [`Endpoint`].
+///
+/// Lost connections are retried with exponential backoff and jitter, and a connection whose
+/// server stops answering pings is dropped.
+pub fn connect(endpoint: Endpoint) -> impl Stream<Item = Event> {
     stream::channel(100, |mut output| async move {
-        let mut state = State::Disconnected;
+        let mut attempt = 0;
 
         loop {
-            match &mut state {
-                State::Disconnected => {
-                    const ECHO_SERVER: &str = "ws://127.0.0.1:3030";
-
-                    match async_tungstenite::tokio::connect_async(ECHO_SERVER)
-                        .await
-                    {
-                        Ok((websocket, _)) => {
-                            let (sender, receiver) = mpsc::channel(100);
-
-                            let _ = output
-                                .send(Event::Connected(Connection(sender)))
-                                .await;
-
-                            state = State::Connected(websocket, receiver);
-                        }
-                        Err(_) => {
-                            tokio::time::sleep(
-                                tokio::time::Duration::from_secs(1),
-                            )
-                            .await;
-
-                            let _ = output.send(Event::Disconnected).await;
-                        }
-                    }
-                }
-                State::Connected(websocket, input) => {
-                    let mut fused_websocket = websocket.by_ref().fuse();
-
-                    futures::select! {
-                        received = fused_websocket.select_next_some() => {
-                            match received {
-                                Ok(tungstenite::Message::Text(message)) => {
-                                   let _ = output.send(Event::MessageReceived(Message::User(message))).await;
-                                }
-                                Err(_) => {
-                                    let _ = output.send(Event::Disconnected).await;
-
-                                    state = State::Disconnected;
-                                }
-                                Ok(_) => continue,
-                            }
-                        }
-
-                        message = input.select_next_some() => {
-                            let result = websocket.send(tungstenite::Message::Text(message.to_string())).await;
-
-                            if result.is_err() {
-                                let _ = output.send(Event::Disconnected).await;
-
-                                state = State::Disconnected;
-                            }
-                        }
-                    }
+            let reason = match endpoint.connect().await {
+                Ok(websocket) => {
+                    attempt = 0;
+
+                    let (sender, receiver) = mpsc::channel(100);
+                    let _ = output.send(Event::Connected(Connection(sender))).await;
+
+                    let disconnection = run(websocket, receiver, &mut output).await;
+                    let reason = disconnection.to_string();
+                    let _ = output.send(Event::Disconnected(disconnection)).await;
+                    reason
                 }
-            }
+                Err(reason) => reason,
+            };
+
+            attempt += 1;
+            let delay = backoff(attempt);
+            let _ = output
+                .send(Event::Reconnecting {
+                    attempt,
+                    delay,
+                    reason,
+                })
+                .await;
+
+            tokio::time::sleep(delay).await;
         }
     })
 }
 
-#[derive(Debug)]
-#[allow(clippy::large_enum_variant)]
-enum State {
-    Disconnected,
-    Connected(
-        async_tungstenite::WebSocketStream<
-            async_tungstenite::tokio::ConnectStream,
-        >,
-        mpsc::Receiver<Message>,
-    ),
+/// Returns the delay before the given reconnection attempt, starting at 1.
+///
+/// The delay is randomly shortened by up to half, so clients that lost the server at the same
+/// time do not all come back at once.
+fn backoff(attempt: u32) -> Duration {
+    let delay = INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF);
+
+    delay.mul_f64(rand::rng().random_range(0.5..=1.0))
+}
+
+/// Exchanges messages with the server until the connection is lost.
+async fn run(
+    websocket: async_tungstenite::WebSocketStream<async_tungstenite::tokio::ConnectStream>,
+    mut input: mpsc::Receiver<Message>,
+    output: &mut mpsc::Sender<Event>,
+) -> Disconnection {
+    let mut websocket = websocket.fuse();
+    let mut ping = tokio::time::interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut ping_sent: Option<Instant> = None;
+
+    loop {
+        let pong_timeout = match ping_sent {
+            Some(sent) => tokio::time::sleep_until(sent + PONG_TIMEOUT).left_future(),
+            None => future::pending().right_future(),
+        };
+
+        futures::select! {
+            received = websocket.next() => match received {
+                Some(Ok(tungstenite::Message::Text(message))) => {
+                    let _ = output.send(Event::MessageReceived(Message::User(message))).await;
+                }
+                Some(Ok(tungstenite::Message::Pong(_))) => {
+                    ping_sent = None;
+                }
+                Some(Ok(tungstenite::Message::Close(frame))) => {
+                    return Disconnection::closed(frame);
+                }
+                // Pings are answered by tungstenite, and the server sends no binary messages.
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Disconnection::error(error.to_string()),
+                None => return Disconnection::error("connection ended".to_string()),
+            },
+
+            message = input.select_next_some() => {
+                let result = websocket.send(tungstenite::Message::Text(message.to_string())).await;
+
+                if let Err(error) = result {
+                    return Disconnection::error(error.to_string());
+                }
+            }
+
+            _ = ping.tick().fuse() => {
+                if ping_sent.is_none() {
+                    if let Err(error) = websocket.send(tungstenite::Message::Ping(Vec::new())).await {
+                        return Disconnection::error(error.to_string());
+                    }
+                    ping_sent = Some(Instant::now());
+                }
+            }
+
+            () = pong_timeout.fuse() => {
+                let frame = CloseFrame {
+                    code: CloseCode::Away,
+                    reason: format!("no pong within {}s", PONG_TIMEOUT.as_secs()).into(),
+                };
+                let _ = websocket.send(tungstenite::Message::Close(Some(frame.clone()))).await;
+
+                return Disconnection::closed(Some(frame));
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
     Connected(Connection),
-    Disconnected,
+    Disconnected(Disconnection),
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+        /// Why the previous attempt or connection failed.
+        reason: String,
+    },
     MessageReceived(Message),
 }
 
+/// Why a connection to the echo server was lost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disconnection {
+    /// The close code of the side that closed the connection, if it was closed: the server, or
+    /// the client when the server stopped answering pings.
+    pub code: Option<u16>,
+    pub reason: String,
+}
+
+impl Disconnection {
+    fn closed(frame: Option<CloseFrame<'_>>) -> Self {
+        match frame {
+            Some(frame) => Self {
+                code: Some(frame.code.into()),
+                reason: frame.reason.into_owned(),
+            },
+            None => Self {
+                code: None,
+                reason: "closed by the server".to_string(),
+            },
+        }
+    }
+
+    fn error(reason: String) -> Self {
+        Self { code: None, reason }
+    }
+}
+
+impl fmt::Display for Disconnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.code, self.reason.is_empty()) {
+            (Some(code), true) => write!(f, "closed with code {code}"),
+            (Some(code), false) => write!(f, "closed with code {code}: {}", self.reason),
+            (None, _) => f.write_str(&self.reason),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Connection(mpsc::Sender<Message>);
 
 impl Connection {
-    pub fn send(&mut self, message: Message) {
-        self.0
-            .try_send(message)
-            .expect("Send message to echo server");
+    /// Queues a message for the server, or returns it if it cannot be queued.
+    pub fn send(&mut self, message: Message) -> Result<(), SendError> {
+        self.0.try_send(message).map_err(|error| {
+            if error.is_full() {
+                SendError::Full(error.into_inner())
+            } else {
+                SendError::Disconnected(error.into_inner())
+            }
+        })
+    }
+}
+
+/// A message that could not be queued for the server.
+#[derive(Debug, Clone)]
+pub enum SendError {
+    /// The server is not keeping up; try again later.
+    Full(Message),
+    /// The connection was lost.
+    Disconnected(Message),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Full(_) => f.write_str("Too many messages waiting to be sent, try again"),
+            SendError::Disconnected(_) => f.write_str("Not connected"),
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Connected,
-    Disconnected,
+    Disconnected(String),
+    Notice(String),
     User(String),
 }
 
@@ -126,14 +248,25 @@ impl Message {
         Message::Connected
     }
 
-    pub fn disconnected() -> Self {
-        Message::Disconnected
+    pub fn disconnected(disconnection: &Disconnection) -> Self {
+        Message::Disconnected(format!("Connection lost ({disconnection})"))
+    }
+
+    pub fn reconnecting(attempt: u32, delay: Duration, reason: &str) -> Self {
+        Message::Notice(format!(
+            "{reason}; retrying in {:.1}s (attempt {attempt})...",
+            delay.as_secs_f32()
+        ))
+    }
+
+    pub fn notice(notice: String) -> Self {
+        Message::Notice(notice)
     }
 
     pub fn as_str(&self) -> &str {
         match self {
             Message::Connected => "Connected successfully!",
-            Message::Disconnected => "Connection lost... Retrying...",
+            Message::Disconnected(message) | Message::Notice(message) => message.as_str(),
             Message::User(message) => message.as_str(),
         }
     }
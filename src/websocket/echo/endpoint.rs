@@ -0,0 +1,242 @@
+//! Where the echo client connects.
+//!
+//! The [`Endpoint`] is read once at startup from the environment:
+//!
+//! | Variable | Meaning | Default |
+//! |---|---|---|
+//! | `ECHO_URL` | `ws://` or `wss://` URL of the server | `ws://127.0.0.1:3030` |
+//! | `ECHO_HEADERS` | Extra handshake headers, as `Name: value` lines | none |
+//! | `ECHO_PROTOCOLS` | Comma-separated subprotocols to request | none |
+//! | `ECHO_ROOT_CERTIFICATES` | Comma-separated PEM files of roots trusted for `wss://` | none |
+//!
+//! Headers are separated by newlines rather than `;`, which values like `Cookie` contain.
+//!
+//! The extra root certificates are trusted on top of the bundled web PKI roots, so a server
+//! with a self-signed certificate can be reached without giving up on public ones. They are
+//! read once, not on every reconnection.
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_tungstenite::tokio::ConnectStream;
+use async_tungstenite::tungstenite::client::IntoClientRequest;
+use async_tungstenite::tungstenite::handshake::client::Request;
+use async_tungstenite::tungstenite::http::header::{HeaderName, HeaderValue};
+use async_tungstenite::WebSocketStream;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+const DEFAULT_URL: &str = "ws://127.0.0.1:3030";
+
+/// The URL of the echo server and how to reach it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    pub url: String,
+    /// Extra headers of the handshake request, like an `Authorization` token.
+    pub headers: Vec<(String, String)>,
+    /// PEM files of the extra roots trusted for `wss://`.
+    pub root_certificates: Vec<PathBuf>,
+    connector: Connector,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_URL.to_string(),
+            headers: Vec::new(),
+            root_certificates: Vec::new(),
+            connector: Connector::default(),
+        }
+    }
+}
+
+impl Endpoint {
+    /// Reads the endpoint from the environment and checks that it can be used.
+    pub fn from_env() -> Result<Self, Error> {
+        let mut endpoint = Self::default();
+
+        if let Ok(url) = env::var("ECHO_URL") {
+            endpoint.url = url.trim().to_string();
+        }
+
+        if let Ok(headers) = env::var("ECHO_HEADERS") {
+            for header in headers.lines().filter(|header| !header.trim().is_empty()) {
+                let (name, value) = header
+                    .split_once(':')
+                    .ok_or_else(|| Error::Header(header.trim().to_string()))?;
+
+                endpoint.headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        if let Ok(protocols) = env::var("ECHO_PROTOCOLS") {
+            let protocols: Vec<_> = protocols
+                .split(',')
+                .map(str::trim)
+                .filter(|protocol| !protocol.is_empty())
+                .collect();
+
+            if !protocols.is_empty() {
+                endpoint
+                    .headers
+                    .push(("Sec-WebSocket-Protocol".to_string(), protocols.join(", ")));
+            }
+        }
+
+        if let Ok(paths) = env::var("ECHO_ROOT_CERTIFICATES") {
+            endpoint.root_certificates = paths
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .collect();
+        }
+
+        let _ = endpoint.request()?;
+        endpoint.connector = Connector(endpoint.tls_connector()?);
+
+        Ok(endpoint)
+    }
+
+    /// Returns the same endpoint at another URL, if the URL can be used.
+    pub fn with_url(&self, url: &str) -> Result<Self, Error> {
+        let endpoint = Self {
+            url: url.trim().to_string(),
+            ..self.clone()
+        };
+
+        let _ = endpoint.request()?;
+
+        Ok(endpoint)
+    }
+
+    pub fn is_secure(&self) -> bool {
+        self.url.starts_with("wss://")
+    }
+
+    /// Opens a WebSocket to the endpoint.
+    pub(super) async fn connect(&self) -> Result<WebSocketStream<ConnectStream>, String> {
+        let request = self.request().map_err(|error| error.to_string())?;
+        let connector = self.connector.0.clone();
+
+        async_tungstenite::tokio::connect_async_with_tls_connector(request, connector)
+            .await
+            .map(|(websocket, _)| websocket)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Builds the handshake request, with the extra headers.
+    fn request(&self) -> Result<Request, Error> {
+        if !self.url.starts_with("ws://") && !self.url.starts_with("wss://") {
+            return Err(Error::Url(self.url.clone(), "not a ws:// or wss:// URL".to_string()));
+        }
+
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|error| Error::Url(self.url.clone(), error.to_string()))?;
+
+        for (name, value) in &self.headers {
+            let invalid = || Error::Header(format!("{name}: {value}"));
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+            let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+
+            let _ = request.headers_mut().append(name, value);
+        }
+
+        Ok(request)
+    }
+
+    /// Builds the TLS connector trusting the extra roots, if there are any to trust.
+    ///
+    /// It is built even for a `ws://` URL, which [`Endpoint::with_url`] may change to `wss://`.
+    fn tls_connector(&self) -> Result<Option<TlsConnector>, Error> {
+        if self.root_certificates.is_empty() {
+            return Ok(None);
+        }
+
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+        for path in &self.root_certificates {
+            let file = File::open(path).map_err(|error| Error::Io(path.clone(), error))?;
+
+            for certificate in rustls_pemfile::certs(&mut BufReader::new(file)) {
+                let certificate = certificate.map_err(|error| Error::Io(path.clone(), error))?;
+
+                roots
+                    .add(certificate)
+                    .map_err(|error| Error::Certificate(path.clone(), error.to_string()))?;
+            }
+        }
+
+        let config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Ok(Some(TlsConnector::from(Arc::new(config))))
+    }
+}
+
+/// The TLS connector built from the root certificates of an [`Endpoint`].
+///
+/// It is left out of comparisons and hashing, which already cover the root certificates it is
+/// built from.
+#[derive(Clone, Default)]
+struct Connector(Option<TlsConnector>);
+
+impl fmt::Debug for Connector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Connector(extra roots)" } else { "Connector(none)" })
+    }
+}
+
+impl PartialEq for Connector {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Connector {}
+
+impl Hash for Connector {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
+/// A configured [`Endpoint`] that cannot be used.
+#[derive(Debug)]
+pub enum Error {
+    /// An invalid URL, and why.
+    Url(String, String),
+    /// A header that is not `Name: value`, or not a valid HTTP header.
+    Header(String),
+    Io(PathBuf, io::Error),
+    /// A root certificate rejected by rustls, and why.
+    Certificate(PathBuf, String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url, reason) => write!(f, "invalid URL {url}: {reason}"),
+            Self::Header(header) => write!(f, "invalid header: {header}"),
+            Self::Io(path, error) => write!(f, "cannot read {}: {error}", path.display()),
+            Self::Certificate(path, reason) => {
+                write!(f, "invalid root certificate in {}: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -7,32 +7,49 @@ use iced::{color, Center, Element, Fill, Subscription, Task};
 use std::sync::LazyLock;
 
 pub fn main() -> iced::Result {
+    let endpoint = match echo::Endpoint::from_env() {
+        Ok(endpoint) => endpoint,
+        Err(error) => {
+            eprintln!("Invalid echo endpoint: {error}");
+            std::process::exit(1);
+        }
+    };
+
     iced::application("WebSocket - Iced", WebSocket::update, WebSocket::view)
         .subscription(WebSocket::subscription)
-        .run_with(WebSocket::new)
+        .run_with(move || WebSocket::new(endpoint))
 }
 
 struct WebSocket {
     messages: Vec<echo::Message>,
     new_message: String,
     state: State,
+    endpoint: echo::Endpoint,
+    /// The URL being typed, applied on [`Message::ChangeEndpoint`].
+    endpoint_url: String,
+    endpoint_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     NewMessageChanged(String),
+    EndpointUrlChanged(String),
+    ChangeEndpoint,
     Send(echo::Message),
     Echo(echo::Event),
     Server,
 }
 
 impl WebSocket {
-    fn new() -> (Self, Task<Message>) {
+    fn new(endpoint: echo::Endpoint) -> (Self, Task<Message>) {
         (
             Self {
                 messages: Vec::new(),
                 new_message: String::new(),
                 state: State::Disconnected,
+                endpoint_url: endpoint.url.clone(),
+                endpoint,
+                endpoint_error: None,
             },
             Task::batch([
                 Task::perform(echo::server::run(), |_| Message::Server),
@@ -48,11 +65,42 @@ impl WebSocket {
 
                 Task::none()
             }
+            Message::EndpointUrlChanged(url) => {
+                self.endpoint_url = url;
+                self.endpoint_error = None;
+
+                Task::none()
+            }
+            Message::ChangeEndpoint => {
+                match self.endpoint.with_url(&self.endpoint_url) {
+                    // The subscription is keyed by the endpoint, so changing it
+                    // drops the current connection and connects to the new one.
+                    Ok(endpoint) if endpoint != self.endpoint => {
+                        self.messages.push(echo::Message::notice(format!(
+                            "Connecting to {endpoint}..."
+                        )));
+
+                        self.endpoint = endpoint;
+                        self.state = State::Disconnected;
+                    }
+                    Ok(_) => {}
+                    Err(error) => self.endpoint_error = Some(error.to_string()),
+                }
+
+                Task::none()
+            }
             Message::Send(message) => match &mut self.state {
                 State::Connected(connection) => {
-                    self.new_message.clear();
+                    match connection.send(message) {
+                        Ok(()) => self.new_message.clear(),
+                        Err(error) => {
+                            if let echo::SendError::Disconnected(_) = error {
+                                self.state = State::Disconnected;
+                            }
 
-                    connection.send(message);
+                            self.messages.push(echo::Message::notice(error.to_string()));
+                        }
+                    }
 
                     Task::none()
                 }
@@ -66,10 +114,15 @@ impl WebSocket {
 
                     Task::none()
                 }
-                echo::Event::Disconnected => {
+                echo::Event::Disconnected(disconnection) => {
                     self.state = State::Disconnected;
 
-                    self.messages.push(echo::Message::disconnected());
+                    self.messages.push(echo::Message::disconnected(&disconnection));
+
+                    Task::none()
+                }
+                echo::Event::Reconnecting { attempt, delay, reason } => {
+                    self.messages.push(echo::Message::reconnecting(attempt, delay, &reason));
 
                     Task::none()
                 }
@@ -87,10 +140,42 @@ impl WebSocket {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::run(echo::connect).map(Message::Echo)
+        Subscription::run_with_id(
+            self.endpoint.clone(),
+            echo::connect(self.endpoint.clone()),
+        )
+        .map(Message::Echo)
     }
 
     fn view(&self) -> Element<Message> {
+        let endpoint = {
+            let changed = self.endpoint_url.trim() != self.endpoint.url;
+
+            let input = text_input("ws://127.0.0.1:3030", &self.endpoint_url)
+                .on_input(Message::EndpointUrlChanged)
+                .on_submit_maybe(changed.then_some(Message::ChangeEndpoint))
+                .padding(10);
+
+            let button = button(text("Connect").height(40).align_y(Center))
+                .padding([0, 20])
+                .on_press_maybe(changed.then_some(Message::ChangeEndpoint));
+
+            let status = match (&self.endpoint_error, &self.state) {
+                (Some(error), _) => text(error).style(text::danger),
+                (None, State::Connected(_)) if self.endpoint.is_secure() => {
+                    text("Connected (TLS)").style(text::success)
+                }
+                (None, State::Connected(_)) => {
+                    text("Connected").style(text::success)
+                }
+                (None, State::Disconnected) => {
+                    text("Disconnected").style(text::secondary)
+                }
+            };
+
+            row![input, button, status].spacing(10).align_y(Center)
+        };
+
         let message_log: Element<_> = if self.messages.is_empty() {
             center(
                 text("Your messages will appear here...")
@@ -125,7 +210,7 @@ impl WebSocket {
             row![input, button].spacing(10).align_y(Center)
         };
 
-        column![message_log, new_message_input]
+        column![endpoint, message_log, new_message_input]
             .height(Fill)
             .padding(20)
             .spacing(10)
@@ -1,45 +1,328 @@
-use std::{env, str::from_utf8};
+use std::fmt;
+use std::time::Duration;
 use iced::futures::channel::mpsc;
-use async_nats::jetstream::{self, consumer::PullConsumer};
+use async_nats::jetstream::{self, AckKind};
+use async_nats::jetstream::consumer::{PullConsumer, StreamError};
+use async_nats::jetstream::consumer::pull::{MessagesError, MessagesErrorKind, Stream as Messages};
+use async_nats::jetstream::context::GetStreamError;
+use async_nats::jetstream::stream::ConsumerError;
+use async_nats::ConnectOptions;
 use iced::stream;
+use iced::time::Instant;
+use futures::sink::SinkExt;
 use futures::stream::{Stream, StreamExt};
 
+use crate::nats::config;
+use crate::replay::{self, Recorder, Source};
+use crate::stream_data::{self, Diagnostic};
 use crate::Message;
 
+/// The delay before the first reconnection attempt, doubled on every further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A change of the connection to the NATS feed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Connecting,
+    Connected,
+    /// Connected, but the consumer missed a heartbeat of the server.
+    Stalled,
+    Disconnected { reason: String },
+    /// The client gave up on the connection and will not reconnect on its own.
+    Closed,
+    Reconnecting { attempt: u32, backoff: Duration },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connecting => write!(f, "Connecting"),
+            Self::Connected => write!(f, "Connected"),
+            Self::Stalled => write!(f, "Connected, but missing heartbeats"),
+            Self::Disconnected { reason } => write!(f, "Disconnected: {reason}"),
+            Self::Closed => write!(f, "Disconnected: connection closed"),
+            Self::Reconnecting { attempt, backoff } => {
+                write!(f, "Reconnecting in {:.1}s (attempt {attempt})", backoff.as_secs_f32())
+            }
+        }
+    }
+}
+
+/// A failure of the NATS feed that requires rebuilding the connection.
+#[derive(Debug)]
+pub enum Error {
+    Connect(async_nats::Error),
+    GetStream(GetStreamError),
+    Consumer(ConsumerError),
+    UpdateConsumer(async_nats::Error),
+    Subscribe(StreamError),
+    Receive(MessagesError),
+    Ack(async_nats::Error),
+    Ended,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect(error) => write!(f, "cannot connect: {error}"),
+            Self::GetStream(error) => write!(f, "cannot get stream: {error}"),
+            Self::Consumer(error) => write!(f, "cannot create consumer: {error}"),
+            Self::UpdateConsumer(error) => write!(f, "cannot update consumer: {error}"),
+            Self::Subscribe(error) => write!(f, "cannot subscribe: {error}"),
+            Self::Receive(error) => write!(f, "cannot receive: {error}"),
+            Self::Ack(error) => write!(f, "cannot ack: {error}"),
+            Self::Ended => write!(f, "message stream ended"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Streams the connection events and decoded summaries of the JetStream consumer.
+///
+/// A single client and consumer are kept for as long as they work; the client reconnects on
+/// its own and reports it through [`client_events`], so the connection is only rebuilt, with
+/// exponential backoff, when subscribing or the message stream fails.
+///
+/// Every received payload is also written to the recording of the [`Source`], if any.
 pub fn connect() -> impl Stream<Item = Message> {
     stream::channel(100, |mut output| async move {
+        let mut attempt = 0;
+        let mut recorder = match replay::get() {
+            Source::Live { record: Some(path) } => match Recorder::create(path) {
+                Ok(recorder) => Some(recorder),
+                Err(error) => {
+                    eprintln!("Cannot record to {}: {error}", path.display());
+                    None
+                }
+            },
+            _ => None,
+        };
+
         loop {
-            let nats_url = env::var("NATS_URL").unwrap_or_else(|_| "nats://43.206.231.234:4222".to_string());
+            let _ = output.send(Message::Nats(Event::Connecting)).await;
 
-            let client = async_nats::connect(nats_url).await.unwrap();
+            let error = match subscribe().await {
+                Ok((messages, events, warning)) => {
+                    attempt = 0;
+                    let _ = output.send(Message::Nats(Event::Connected)).await;
+                    if let Some(warning) = warning {
+                        let diagnostic = Diagnostic::warning(warning);
+                        let _ = output.send(Message::Diagnostic(diagnostic)).await;
+                    }
 
-            // let mut vector = vec![];
+                    match forward(messages, events, &mut output, recorder.as_mut()).await {
+                        Ok(()) => return,
+                        Err(error) => error,
+                    }
+                }
+                Err(error) => error,
+            };
 
-            let jetstream = jetstream::new(client);
+            eprintln!("NATS feed failed: {error}");
+            let _ = output.send(Message::Nats(Event::Disconnected { reason: error.to_string() })).await;
 
-            let consumer: PullConsumer = jetstream.get_stream("mm_perp_summary_derive").await.unwrap()
-            .create_consumer(jetstream::consumer::pull::Config {
-                durable_name: Some("consumer".into()),
-                ..Default::default()
-            })
-            .await.unwrap();
+            attempt += 1;
+            let backoff = backoff(attempt);
+            let _ = output.send(Message::Nats(Event::Reconnecting { attempt, backoff })).await;
 
-            let mut messages = consumer.messages().await.unwrap().take(1);
+            tokio::time::sleep(backoff).await;
+        }
+    })
+}
 
-            match messages.next().await {
-                Some(msg) => {
-                    let payload = msg.as_ref().unwrap().payload.clone();
-                    output.try_send(Message::NatsMessageReceived { payload: payload.clone().to_vec() });
-                },
-                None => {},
+/// Returns the delay before the given reconnection attempt, starting at 1.
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Reports the connection changes of a client as [`Event`]s on `sender`.
+///
+/// The client reconnects on its own, with the same backoff as the feed after an immediate
+/// first attempt.
+pub fn client_events(
+    options: ConnectOptions,
+    sender: mpsc::UnboundedSender<Event>,
+) -> ConnectOptions {
+    let delays = sender.clone();
+
+    options
+        .event_callback(move |event| {
+            let sender = sender.clone();
+            async move {
+                let event = match event {
+                    async_nats::Event::Connected => Event::Connected,
+                    async_nats::Event::Disconnected => Event::Disconnected {
+                        reason: "lost the connection to the server".to_string(),
+                    },
+                    async_nats::Event::Closed => Event::Closed,
+                    _ => return,
+                };
+                let _ = sender.unbounded_send(event);
+            }
+        })
+        .reconnect_delay_callback(move |attempts| {
+            // The client counts its immediate first attempt too.
+            let attempt = u32::try_from(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+            if attempt == 0 {
+                return Duration::ZERO;
             }
 
-            // Iterate over messages.
-            // while let Some(message) = messages.next().await {
-            //     let message = message.unwrap();
-            //     vector.push(from_utf8(&message.payload).unwrap().to_string());
-            //     message.ack().await.unwrap();
-            // }
+            let backoff = backoff(attempt);
+            let _ = delays.unbounded_send(Event::Reconnecting { attempt, backoff });
+            backoff
+        })
+}
+
+/// Connects to NATS and opens the message stream of the durable pull consumer, along with the
+/// [`Event`]s of the client and a warning if the consumer does not match its configuration.
+async fn subscribe() -> Result<(Messages, mpsc::UnboundedReceiver<Event>, Option<String>), Error> {
+    let config = config::get();
+
+    let (sender, events) = mpsc::unbounded();
+    let client = config
+        .connect_with(|options| client_events(options, sender))
+        .await
+        .map_err(Error::Connect)?;
+
+    let jetstream = jetstream::new(client);
+
+    let stream = jetstream.get_stream(&config.stream).await.map_err(Error::GetStream)?;
+    let (consumer, warning) = durable_consumer(&stream).await?;
+
+    let messages = consumer.messages().await.map_err(Error::Subscribe)?;
+
+    Ok((messages, events, warning))
+}
+
+/// Returns the durable pull consumer, creating it or updating its filter subject to match the
+/// configuration.
+///
+/// The server cannot change the deliver policy of an existing consumer, so a different one only
+/// produces a warning.
+async fn durable_consumer(
+    stream: &jetstream::stream::Stream,
+) -> Result<(PullConsumer, Option<String>), Error> {
+    let config = config::get();
+    let wanted = config.consumer_config();
+
+    let mut consumer: PullConsumer = stream
+        .get_or_create_consumer(&config.consumer, wanted.clone())
+        .await
+        .map_err(Error::Consumer)?;
+    let existing = consumer.cached_info().config.clone();
+
+    if existing.filter_subject != wanted.filter_subject {
+        consumer = stream
+            .update_consumer(wanted.clone())
+            .await
+            .map_err(|error| Error::UpdateConsumer(Box::new(error)))?;
+    }
+
+    let warning = (existing.deliver_policy != wanted.deliver_policy).then(|| {
+        format!(
+            "consumer {} keeps its deliver policy {:?} instead of the configured {:?}; delete \
+             the consumer to apply it",
+            config.consumer, existing.deliver_policy, wanted.deliver_policy
+        )
+    });
+
+    Ok((consumer, warning))
+}
+
+/// Forwards the client [`Event`]s and every message that decodes into
+/// [`StreamData`](stream_data::StreamData), acking it, until the message stream fails or ends.
+///
+/// Messages that fail to decode are terminated, so they are not redelivered. Returns `Ok` once
+/// the subscription is gone.
+async fn forward(
+    messages: Messages,
+    mut events: mpsc::UnboundedReceiver<Event>,
+    output: &mut mpsc::Sender<Message>,
+    mut recorder: Option<&mut Recorder>,
+) -> Result<(), Error> {
+    let mut messages = messages.fuse();
+    let mut stalled = false;
+
+    loop {
+        let message = futures::select! {
+            message = messages.next() => message,
+            event = events.select_next_some() => {
+                stalled &= event != Event::Connected;
+                if output.send(Message::Nats(event)).await.is_err() {
+                    return Ok(());
+                }
+                continue;
+            }
+        };
+
+        let message = match message {
+            Some(Ok(message)) => message,
+            Some(Err(error)) if matches!(error.kind(), MessagesErrorKind::MissingHeartbeat) => {
+                // The consumer recovers from missed heartbeats by itself, so the feed counts as
+                // stalled until the next message.
+                if !stalled {
+                    stalled = true;
+                    if output.send(Message::Nats(Event::Stalled)).await.is_err() {
+                        return Ok(());
+                    }
+                }
+                continue;
+            }
+            Some(Err(error)) => return Err(Error::Receive(error)),
+            None => return Err(Error::Ended),
+        };
+
+        if stalled {
+            stalled = false;
+            if output.send(Message::Nats(Event::Connected)).await.is_err() {
+                return Ok(());
+            }
         }
-    })
-}
\ No newline at end of file
+
+        if let Some(recorder) = recorder.as_mut() {
+            if let Err(error) = recorder.write(&message.subject, &message.payload) {
+                eprintln!("Cannot record message: {error}");
+            }
+        }
+
+        let forwarded = match decode(&message.subject, &message.payload, Instant::now()) {
+            Ok(forwarded) => {
+                message.ack().await.map_err(Error::Ack)?;
+                forwarded
+            }
+            Err(forwarded) => {
+                message.ack_with(AckKind::Term).await.map_err(Error::Ack)?;
+                forwarded
+            }
+        };
+
+        for message in forwarded {
+            if output.send(message).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Decodes a payload received at `time` into the messages to forward for it.
+///
+/// Decoding problems are forwarded as [`Diagnostic`]s. Returns `Err` if the payload could not
+/// be decoded at all.
+pub fn decode(subject: &str, payload: &[u8], time: Instant) -> Result<Vec<Message>, Vec<Message>> {
+    match stream_data::decode(payload) {
+        Ok((stream_data, warnings)) => {
+            let mut forwarded = vec![Message::NatsMessageReceived(stream_data, time)];
+            forwarded.extend(warnings.into_iter().map(|warning| {
+                Message::Diagnostic(Diagnostic::warning(format!("{subject}: {warning}")))
+            }));
+            Ok(forwarded)
+        }
+        Err(error) => Err(vec![Message::Diagnostic(Diagnostic::error(format!(
+            "{subject}: {error}"
+        )))]),
+    }
+}